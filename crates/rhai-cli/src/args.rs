@@ -38,6 +38,8 @@ pub enum RootCommand {
     /// Format Rhai source code.
     #[clap(visible_aliases = &["format"])]
     Fmt(FmtCommand),
+    /// Print the analysed HIR of Rhai source code.
+    Hir(HirCommand),
 }
 
 #[derive(Clone, Subcommand)]
@@ -82,6 +84,27 @@ pub struct FmtCommand {
     pub files: Option<String>,
 }
 
+#[derive(Clone, Parser)]
+pub struct HirCommand {
+    /// The output format.
+    #[clap(long, default_value = "text")]
+    pub format: HirFormat,
+
+    /// Optional pattern to search for files.
+    ///
+    /// If not provided, it will be determined by
+    /// the configuration.
+    pub files: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum HirFormat {
+    /// Unstable textual representation for debugging.
+    Text,
+    /// Stable and versioned JSON representation.
+    Json,
+}
+
 #[derive(Clone, Copy, ValueEnum)]
 pub enum Colors {
     /// Determine whether to colorize output automatically.
@@ -0,0 +1,70 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use rhai_common::{environment::Environment, util::Normalize};
+use rhai_hir::fmt::HirFmt;
+use rhai_rowan::{util::is_rhai_def, Parser};
+use tokio::io::AsyncWriteExt;
+use url::Url;
+
+use crate::{
+    args::{HirCommand, HirFormat},
+    Rhai,
+};
+
+impl<E: Environment> Rhai<E> {
+    pub async fn execute_hir(&mut self, cmd: HirCommand) -> Result<(), anyhow::Error> {
+        let cwd = self
+            .env
+            .cwd()
+            .context("invalid working directory")?
+            .normalize();
+
+        let mut hir = self.load_hir(&cwd).await?;
+
+        if let Some(mut files) = cmd.files {
+            if self.env.is_dir(Path::new(&files)) {
+                files = PathBuf::from(files)
+                    .join("**/*.rhai")
+                    .normalize()
+                    .to_string_lossy()
+                    .into();
+            }
+
+            self.config.source.include = Some(vec![files]);
+            self.config.source.exclude = None;
+        }
+
+        self.config.prepare(&self.env, &cwd)?;
+
+        // Definitions are already loaded.
+        for path in self.collect_files(&cwd, &self.config, true).await? {
+            let f = self.env.read_file(&path).await?;
+            let source = String::from_utf8_lossy(&f).into_owned();
+
+            if is_rhai_def(&source) {
+                continue;
+            }
+
+            let url = Url::parse(&format!("file://{}", path.to_string_lossy())).unwrap();
+            let script = Parser::new(&source)
+                .with_operators(hir.parser_operators())
+                .parse_script()
+                .into_syntax();
+            hir.add_source(&url, &script);
+        }
+
+        hir.resolve_all();
+
+        let out = match cmd.format {
+            HirFormat::Text => HirFmt::new(&hir).with_source().to_string(),
+            HirFormat::Json => serde_json::to_string_pretty(&hir.export())?,
+        };
+
+        let mut stdout = self.env.stdout();
+        stdout.write_all(out.as_bytes()).await?;
+        stdout.flush().await?;
+
+        Ok(())
+    }
+}
@@ -6,11 +6,12 @@ use rhai_common::environment::Environment;
 
 mod config;
 mod fmt;
+mod hir;
 mod lsp;
 
 impl<E: Environment> Rhai<E> {
     pub async fn execute(&mut self, args: RhaiArgs) -> Result<(), anyhow::Error> {
-        if let RootCommand::Fmt(_) = &args.cmd {
+        if let RootCommand::Fmt(_) | RootCommand::Hir(_) = &args.cmd {
             self.load_config(&args).await?
         }
 
@@ -24,6 +25,7 @@ impl<E: Environment> Rhai<E> {
             RootCommand::Lsp { cmd } => self.execute_lsp(cmd).await,
             RootCommand::Config { cmd } => self.execute_config(cmd).await,
             RootCommand::Fmt(cmd) => self.execute_fmt(cmd).await,
+            RootCommand::Hir(cmd) => self.execute_hir(cmd).await,
         }
     }
 }
@@ -19,7 +19,7 @@ use codespan_reporting::{
 use figment::{providers::Serialized, Figment};
 use itertools::Itertools;
 use rhai_common::{config::Config, environment::Environment, util::Normalize};
-use rhai_hir::Hir;
+use rhai_hir::{builtin::StdPackage, metadata::Metadata, module::FileModuleResolver, Hir};
 use rhai_rowan::{TextRange, util::is_rhai_def, Parser};
 use tokio::io::AsyncWriteExt;
 use url::Url;
@@ -173,6 +173,35 @@ impl<E: Environment> Rhai<E> {
             &self.env,
             &self.env.cwd().context("invalid working directory")?,
         )?;
+        hir.set_std_packages(StdPackage::select(self.config.std.packages())?);
+        let modules = &self.config.modules;
+        hir.set_import_resolver(FileModuleResolver::from_config(
+            modules.base_url(),
+            modules.search_urls(),
+            modules.alias_urls(),
+            modules.extension.as_deref(),
+        ));
+        hir.set_fn_name_functions(
+            self.config
+                .source
+                .fn_name_functions
+                .clone()
+                .unwrap_or_default(),
+        );
+        for file in self.config.source.metadata.iter().flatten() {
+            let bytes = self
+                .env
+                .read_file(Path::new(file))
+                .await
+                .with_context(|| format!("failed to read metadata file {file}"))?;
+            let metadata: Metadata = serde_json::from_slice(&bytes)
+                .with_context(|| format!("invalid metadata file {file}"))?;
+            let Ok(url) = Url::from_file_path(file) else {
+                tracing::warn!(path = %file, "metadata path is not a valid file URL");
+                continue;
+            };
+            hir.add_metadata(&url, &metadata);
+        }
         for file in self.collect_files(cwd, &self.config, false).await? {
             let bytes = match self.env.read_file(&file).await {
                 Ok(f) => f,
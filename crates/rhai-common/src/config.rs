@@ -4,7 +4,8 @@ use crate::{
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::{collections::BTreeMap, path::Path};
+use url::Url;
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
@@ -12,11 +13,17 @@ pub struct Config {
     pub source: SourceConfig,
     #[serde(default)]
     pub fmt: FmtConfig,
+    #[serde(default)]
+    pub std: StdConfig,
+    #[serde(default)]
+    pub modules: ModulesConfig,
 }
 
 impl Config {
     pub fn prepare(&mut self, e: &impl Environment, base: &Path) -> anyhow::Result<()> {
-        self.source.prepare(e, base)
+        self.source.prepare(e, base)?;
+        self.modules.prepare(e, base);
+        Ok(())
     }
 }
 
@@ -28,6 +35,19 @@ pub struct SourceConfig {
     /// A list of UNIX-style glob patterns.
     /// For Rhai files that should be excluded.
     pub exclude: Option<Vec<String>>,
+    /// Paths to JSON files with the metadata of functions
+    /// registered by the host, as generated by `Engine::gen_fn_metadata_to_json`.
+    ///
+    /// The functions are available in scripts
+    /// without having to write definitions for them.
+    pub metadata: Option<Vec<String>>,
+    /// Names of functions registered by the host that take
+    /// the name of a script function as their first argument,
+    /// e.g. `call_fn`.
+    ///
+    /// String literals passed to them refer to the named functions,
+    /// the same way as in `Fn("name")`.
+    pub fn_name_functions: Option<Vec<String>>,
 
     #[serde(skip)]
     pub file_rule: Option<GlobRule>,
@@ -84,6 +104,129 @@ impl SourceConfig {
                 }
             }
         }
+
+        if let Some(metadata) = &mut self.metadata {
+            for path in metadata {
+                if !e.is_absolute(Path::new(path)) {
+                    *path = base
+                        .join(path.as_str())
+                        .normalize()
+                        .to_string_lossy()
+                        .into_owned();
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StdConfig {
+    /// Built-in Rhai packages whose definitions are available in scripts.
+    ///
+    /// Package names such as `basic-array` or `more-string` can be given,
+    /// as well as the `core` and `standard` package groups.
+    ///
+    /// All packages of the standard package are available by default.
+    pub packages: Option<Vec<String>>,
+}
+
+impl StdConfig {
+    /// The selected packages or package groups.
+    pub fn packages(&self) -> impl Iterator<Item = &str> {
+        self.packages
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .map(String::as_str)
+            .chain(self.packages.is_none().then_some("standard"))
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModulesConfig {
+    /// The directory that import paths are resolved from,
+    /// the same as the base path of Rhai's `FileModuleResolver`.
+    ///
+    /// By default import paths are resolved relative to the importing script.
+    pub base_path: Option<String>,
+    /// Additional directories that are searched in order
+    /// for modules that are not found in the base path.
+    pub search_paths: Option<Vec<String>>,
+    /// Import paths or path prefixes mapped to other paths,
+    /// e.g. `std = "vendor/std"` resolves `import "std/math"`
+    /// to `vendor/std/math.rhai`.
+    ///
+    /// Modules registered in a `StaticModuleResolver` can be mapped
+    /// to modules of definitions, e.g. `api = "rhai-static://api"`
+    /// for `module api;`.
+    pub aliases: Option<BTreeMap<String, String>>,
+    /// The extension of module files that is appended to import
+    /// paths without it, `rhai` by default.
+    pub extension: Option<String>,
+}
+
+impl ModulesConfig {
+    pub fn prepare(&mut self, e: &impl Environment, base: &Path) {
+        let make_absolute = |path: &mut String| {
+            if !e.is_absolute(Path::new(path.as_str())) && !path.contains("://") {
+                *path = base
+                    .join(path.as_str())
+                    .normalize()
+                    .to_string_lossy()
+                    .into_owned();
+            }
+        };
+
+        self.base_path.iter_mut().for_each(make_absolute);
+        self.search_paths
+            .iter_mut()
+            .flatten()
+            .for_each(make_absolute);
+        self.aliases
+            .iter_mut()
+            .flat_map(BTreeMap::values_mut)
+            .for_each(make_absolute);
+    }
+
+    /// The URL of the base path.
+    ///
+    /// Paths that cannot be converted to URLs are skipped
+    /// here and in the other URL accessors.
+    #[must_use]
+    pub fn base_url(&self) -> Option<Url> {
+        self.base_path.as_deref().and_then(path_url)
+    }
+
+    /// The URLs of the search paths in order.
+    pub fn search_urls(&self) -> impl Iterator<Item = Url> + '_ {
+        self.search_paths
+            .iter()
+            .flatten()
+            .filter_map(|path| path_url(path))
+    }
+
+    /// The aliases with the URLs they are mapped to.
+    pub fn alias_urls(&self) -> impl Iterator<Item = (&str, Url)> + '_ {
+        self.aliases
+            .iter()
+            .flatten()
+            .filter_map(|(alias, target)| Some((alias.as_str(), path_url(target)?)))
+    }
+}
+
+fn path_url(path: &str) -> Option<Url> {
+    let url = if path.contains("://") {
+        Url::parse(path)
+    } else {
+        Url::parse(&format!("file://{path}"))
+    };
+
+    match url {
+        Ok(url) => Some(url),
+        Err(error) => {
+            tracing::warn!(%error, %path, "invalid module path");
+            None
+        }
     }
 }
 
@@ -0,0 +1,170 @@
+//! Definitions of the functions provided by Rhai's built-in packages.
+//!
+//! The definitions are embedded into the static module of the HIR
+//! for the packages selected with [`Hir::set_std_packages`].
+
+use crate::Hir;
+use anyhow::anyhow;
+use itertools::Itertools;
+use rhai_rowan::parser::Parser;
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString, IntoEnumIterator, IntoStaticStr};
+use url::Url;
+
+/// The URL scheme of the sources of built-in definitions.
+pub const STD_URL_SCHEME: &str = "rhai-std";
+
+/// A built-in package of Rhai.
+///
+/// The names match the package names of Rhai without the `Package` suffix,
+/// e.g. [`StdPackage::BasicArray`] is `basic-array` for `BasicArrayPackage`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    EnumString,
+    IntoStaticStr,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum StdPackage {
+    LanguageCore,
+    Arithmetic,
+    Logic,
+    BasicString,
+    MoreString,
+    BasicIterator,
+    BasicFn,
+    BasicArray,
+    BasicBlob,
+    BasicMap,
+    BasicMath,
+    BasicTime,
+    BitField,
+    Debugging,
+}
+
+impl StdPackage {
+    /// The packages of Rhai's `CorePackage`.
+    pub const CORE: &'static [StdPackage] = &[
+        Self::LanguageCore,
+        Self::Arithmetic,
+        Self::Logic,
+        Self::BasicString,
+        Self::BasicIterator,
+        Self::BasicFn,
+        Self::Debugging,
+    ];
+
+    /// All packages of Rhai's `StandardPackage`.
+    pub fn standard() -> impl Iterator<Item = StdPackage> {
+        Self::iter()
+    }
+
+    /// Resolve a selection of package names and the
+    /// `core` and `standard` package groups.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the names is not a known package or group.
+    pub fn select<'n>(
+        names: impl IntoIterator<Item = &'n str>,
+    ) -> Result<Vec<StdPackage>, anyhow::Error> {
+        let mut packages = Vec::new();
+
+        for name in names {
+            match name {
+                "core" => packages.extend_from_slice(Self::CORE),
+                "standard" => packages.extend(Self::standard()),
+                name => packages.push(
+                    name.parse()
+                        .map_err(|_| anyhow!("unknown built-in package `{name}`"))?,
+                ),
+            }
+        }
+
+        Ok(packages)
+    }
+
+    /// The URL of the definition source of the package.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn url(self) -> Url {
+        let name: &'static str = self.into();
+        format!("{STD_URL_SCHEME}:///{name}.d.rhai")
+            .parse()
+            .unwrap()
+    }
+
+    /// The definition source of the package.
+    #[must_use]
+    pub fn definitions(self) -> &'static str {
+        match self {
+            Self::LanguageCore => include_str!("../builtin/core.d.rhai"),
+            Self::Arithmetic => include_str!("../builtin/arithmetic.d.rhai"),
+            Self::Logic => include_str!("../builtin/logic.d.rhai"),
+            Self::BasicString => include_str!("../builtin/string.d.rhai"),
+            Self::MoreString => include_str!("../builtin/more_string.d.rhai"),
+            Self::BasicIterator => include_str!("../builtin/iterator.d.rhai"),
+            Self::BasicFn => include_str!("../builtin/fn.d.rhai"),
+            Self::BasicArray => include_str!("../builtin/array.d.rhai"),
+            Self::BasicBlob => include_str!("../builtin/blob.d.rhai"),
+            Self::BasicMap => include_str!("../builtin/map.d.rhai"),
+            Self::BasicMath => include_str!("../builtin/math.d.rhai"),
+            Self::BasicTime => include_str!("../builtin/time.d.rhai"),
+            Self::BitField => include_str!("../builtin/bit_field.d.rhai"),
+            Self::Debugging => include_str!("../builtin/debugging.d.rhai"),
+        }
+    }
+}
+
+impl Hir {
+    /// Select the built-in packages whose definitions are added
+    /// to the static module, replacing any previous selection.
+    ///
+    /// The selection is kept when the HIR is cleared.
+    pub fn set_std_packages(&mut self, packages: impl IntoIterator<Item = StdPackage>) {
+        let packages = packages.into_iter().unique().collect::<Vec<_>>();
+
+        let removed = self
+            .sources()
+            .filter(|(_, data)| data.url.scheme() == STD_URL_SCHEME)
+            .filter(|(_, data)| !packages.iter().any(|p| p.url() == data.url))
+            .map(|(source, _)| source)
+            .collect::<Vec<_>>();
+
+        for source in removed {
+            self.remove_source(source);
+        }
+
+        self.std_packages = packages;
+        self.ensure_std_packages();
+    }
+
+    /// The currently selected built-in packages.
+    #[must_use]
+    pub fn std_packages(&self) -> &[StdPackage] {
+        &self.std_packages
+    }
+
+    pub(crate) fn ensure_std_packages(&mut self) {
+        for package in self.std_packages.clone() {
+            let url = package.url();
+
+            if self.source_of(&url).is_some() {
+                continue;
+            }
+
+            self.add_source(
+                &url,
+                &Parser::new(package.definitions()).parse_def().into_syntax(),
+            );
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! Structured documentation parsed from doc comments.
+//!
+//! Doc comments are markdown, the following sections are recognized
+//! either as headings (e.g. `# Parameters`) or as tags (e.g. `@param`):
+//!
+//! - parameters: `# Parameters`, `# Params`, `# Arguments` or `@param name description`
+//! - return value: `# Returns` or `@returns description`
+//! - examples: `# Example`, `# Examples` or `@example`
+//! - deprecation: `# Deprecated` or `@deprecated reason`
+//! - version: `# Since` or `@since version`
+//!
+//! Everything else is part of the summary (the first paragraph)
+//! or the description.
+//!
+//! Items can be linked with `` [`name`] `` or `` [`module::name`] ``,
+//! see [`links`] and [`Hir::resolve_doc_link`].
+
+use crate::{
+    scope::ScopeParent,
+    symbol::{ReferenceTarget, SymbolKind, VirtualSymbol},
+    Hir, Scope, Symbol,
+};
+use core::ops::Range;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Docs {
+    /// The first paragraph.
+    pub summary: String,
+    /// Any other text that is not part of a recognized section.
+    pub description: String,
+    pub params: Vec<ParamDocs>,
+    pub returns: Option<String>,
+    /// Examples with code blocks kept as-is.
+    pub examples: Vec<String>,
+    /// The reason of the deprecation, it can be empty.
+    pub deprecated: Option<String>,
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParamDocs {
+    pub name: String,
+    pub docs: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Description,
+    Params,
+    Returns,
+    Examples,
+    Deprecated,
+    Since,
+}
+
+impl Docs {
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let mut docs = Docs::default();
+        let mut description = String::new();
+        let mut section = Section::Description;
+        let mut in_code_block = false;
+
+        for line in text.lines() {
+            let trimmed = line.trim();
+
+            if trimmed.starts_with("```") {
+                in_code_block = !in_code_block;
+            } else if !in_code_block {
+                if let Some(heading) = heading(trimmed) {
+                    section = match heading.to_lowercase().as_str() {
+                        "parameters" | "params" | "arguments" | "args" => Section::Params,
+                        "returns" | "return" | "return value" => Section::Returns,
+                        "example" | "examples" => {
+                            docs.examples.push(String::new());
+                            Section::Examples
+                        }
+                        "deprecated" => {
+                            docs.deprecated.get_or_insert_with(String::new);
+                            Section::Deprecated
+                        }
+                        "since" => Section::Since,
+                        _ => {
+                            push_line(&mut description, line);
+                            Section::Description
+                        }
+                    };
+                    continue;
+                }
+
+                if let Some((tag, rest)) = tag(trimmed) {
+                    match tag {
+                        "param" | "arg" => {
+                            let (name, param_docs) = split_first_word(rest);
+                            docs.params.push(ParamDocs {
+                                name: name.trim_matches('`').to_string(),
+                                docs: param_docs.to_string(),
+                            });
+                            section = Section::Params;
+                            continue;
+                        }
+                        "return" | "returns" => {
+                            push_line(docs.returns.get_or_insert_with(String::new), rest);
+                            section = Section::Returns;
+                            continue;
+                        }
+                        "example" => {
+                            docs.examples.push(rest.to_string());
+                            section = Section::Examples;
+                            continue;
+                        }
+                        "deprecated" => {
+                            push_line(docs.deprecated.get_or_insert_with(String::new), rest);
+                            section = Section::Deprecated;
+                            continue;
+                        }
+                        "since" => {
+                            push_line(docs.since.get_or_insert_with(String::new), rest);
+                            section = Section::Since;
+                            continue;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            match section {
+                Section::Description => push_line(&mut description, line),
+                Section::Params => {
+                    if let Some(param) = list_item(trimmed)
+                        .filter(|_| !in_code_block)
+                        .map(parse_param_item)
+                    {
+                        docs.params.push(param);
+                    } else if let Some(param) = docs.params.last_mut() {
+                        if !trimmed.is_empty() {
+                            push_line(&mut param.docs, trimmed);
+                        }
+                    } else {
+                        push_line(&mut description, line);
+                    }
+                }
+                Section::Returns => push_line(docs.returns.get_or_insert_with(String::new), line),
+                Section::Examples => match docs.examples.last_mut() {
+                    Some(example) => push_line(example, line),
+                    None => docs.examples.push(line.to_string()),
+                },
+                Section::Deprecated => {
+                    push_line(docs.deprecated.get_or_insert_with(String::new), line);
+                }
+                Section::Since => push_line(docs.since.get_or_insert_with(String::new), line),
+            }
+        }
+
+        let description = description.trim();
+        match description.split_once("\n\n") {
+            Some((summary, rest)) => {
+                docs.summary = summary.trim().to_string();
+                docs.description = rest.trim().to_string();
+            }
+            None => docs.summary = description.to_string(),
+        }
+
+        for param in &mut docs.params {
+            param.docs = param.docs.trim().to_string();
+        }
+
+        for text in docs
+            .returns
+            .iter_mut()
+            .chain(docs.deprecated.iter_mut())
+            .chain(docs.since.iter_mut())
+            .chain(docs.examples.iter_mut())
+        {
+            *text = text.trim().to_string();
+        }
+
+        docs.examples.retain(|example| !example.is_empty());
+
+        docs
+    }
+
+    /// The documentation of a parameter by its name.
+    #[must_use]
+    pub fn param(&self, name: &str) -> Option<&ParamDocs> {
+        self.params.iter().find(|p| p.name == name)
+    }
+}
+
+/// The deprecation reason if the docs contain a deprecation marker,
+/// without parsing the rest of the docs.
+#[must_use]
+pub fn deprecation(text: &str) -> Option<String> {
+    if !text.to_lowercase().contains("deprecated") {
+        return None;
+    }
+
+    Docs::parse(text).deprecated
+}
+
+/// An intra-doc link in the form of `` [`target`] ``.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocLink<'d> {
+    /// The byte range of the entire link in the text.
+    pub range: Range<usize>,
+    /// The linked item, e.g. `foo` or `module::foo`.
+    pub target: &'d str,
+}
+
+/// Find all intra-doc links in the given text.
+///
+/// Links that already have a destination (e.g. `` [`foo`](https://example.com) ``)
+/// are skipped.
+pub fn links(text: &str) -> impl Iterator<Item = DocLink<'_>> {
+    let mut offset = 0;
+
+    core::iter::from_fn(move || loop {
+        let start = offset + text[offset..].find("[`")?;
+        let target_start = start + 2;
+        let target_end = target_start + text[target_start..].find("`]")?;
+        let end = target_end + 2;
+        offset = end;
+
+        let target = &text[target_start..target_end];
+
+        if text[end..].starts_with('(') || target.is_empty() || target.contains('`') {
+            offset = target_start;
+            continue;
+        }
+
+        return Some(DocLink {
+            range: start..end,
+            target: target.trim_end_matches("()"),
+        });
+    })
+}
+
+impl Hir {
+    /// Resolve the target of an intra-doc link in the documentation of
+    /// the given symbol.
+    ///
+    /// Unlike references, the target can be declared anywhere in
+    /// the visible scopes.
+    #[must_use]
+    pub fn resolve_doc_link(&self, symbol: Symbol, target: &str) -> Option<Symbol> {
+        let mut segments = target.split("::").map(str::trim);
+        let first = segments.next()?;
+
+        let mut resolved = self.find_visible_by_name(self[symbol].parent_scope, first)?;
+
+        for segment in segments {
+            let module = self.target_module(resolved)?;
+            resolved = self.find_in_module(module, segment)?;
+        }
+
+        // Follow exported references and aliases to the declaration.
+        loop {
+            match &self[resolved].kind {
+                SymbolKind::Ref(r) => match r.target {
+                    Some(ReferenceTarget::Symbol(target)) => resolved = target,
+                    _ => return Some(resolved),
+                },
+                SymbolKind::Virtual(VirtualSymbol::Alias(alias)) => {
+                    resolved = alias.target;
+                }
+                _ => return Some(resolved),
+            }
+        }
+    }
+
+    fn find_visible_by_name(&self, mut scope: Scope, name: &str) -> Option<Symbol> {
+        let is_item = |s: &Symbol| {
+            matches!(
+                self[*s].kind,
+                SymbolKind::Fn(_)
+                    | SymbolKind::Decl(_)
+                    | SymbolKind::Import(_)
+                    | SymbolKind::Virtual(_)
+            ) && self[*s].name(self) == Some(name)
+        };
+
+        loop {
+            if let Some(found) = self.scope_symbols(scope).find(is_item) {
+                return Some(found);
+            }
+
+            scope = match self[scope].parent {
+                Some(ScopeParent::Scope(parent)) => parent,
+                Some(ScopeParent::Symbol(parent)) => self[parent].parent_scope,
+                None => break,
+            };
+        }
+
+        self.scope_symbols(self[self.static_module].scope)
+            .find(is_item)
+    }
+}
+
+fn push_line(text: &mut String, line: &str) {
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text.push_str(line);
+}
+
+fn heading(line: &str) -> Option<&str> {
+    let rest = line.trim_start_matches('#');
+
+    if rest.len() == line.len() || !rest.starts_with(' ') {
+        return None;
+    }
+
+    Some(rest.trim())
+}
+
+fn tag(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix('@')?;
+    let (tag, rest) = split_first_word(rest);
+    Some((tag, rest))
+}
+
+fn split_first_word(text: &str) -> (&str, &str) {
+    let text = text.trim();
+    match text.split_once(char::is_whitespace) {
+        Some((first, rest)) => (first, rest.trim()),
+        None => (text, ""),
+    }
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    line.strip_prefix("* ")
+        .or_else(|| line.strip_prefix("- "))
+        .map(str::trim)
+}
+
+/// Parse items such as `` `name` - docs ``, `name: docs` or `name docs`.
+fn parse_param_item(item: &str) -> ParamDocs {
+    let (name, rest) = if let Some(rest) = item.strip_prefix('`') {
+        match rest.split_once('`') {
+            Some((name, rest)) => (name, rest),
+            None => split_first_word(item),
+        }
+    } else {
+        match item.find(|c: char| c == ':' || c.is_whitespace()) {
+            Some(idx) => item.split_at(idx),
+            None => (item, ""),
+        }
+    };
+
+    let docs = rest.trim_start().trim_start_matches([':', '-', '–']).trim();
+
+    ParamDocs {
+        name: name.trim().to_string(),
+        docs: docs.to_string(),
+    }
+}
@@ -1,4 +1,5 @@
 use crate::Symbol;
+use rhai_rowan::TextRange;
 use thiserror::Error;
 
 #[derive(Debug, Clone, Error)]
@@ -29,8 +30,136 @@ pub enum ErrorKind {
         reference_symbol: Symbol,
         similar_name: Option<String>,
     },
+    #[error("`{name}` is not exported from the module")]
+    PrivateModuleItem {
+        reference_symbol: Symbol,
+        /// The function or variable that is not visible.
+        item: Symbol,
+        name: String,
+    },
     #[error("unresolved import")]
     UnresolvedImport { import: Symbol },
+    #[error("function `{name}` with {arity} parameter(s) is already defined")]
+    DuplicateFnDefinition {
+        duplicate_symbol: Symbol,
+        existing_symbol: Symbol,
+        name: String,
+        arity: usize,
+    },
+    #[error("function `{name}` with {arity} parameter(s) is declared more than once in definition files")]
+    ConflictingFnDefinition {
+        function: Symbol,
+        /// The additional declaration in the definition file.
+        definition: Symbol,
+        name: String,
+        arity: usize,
+    },
+    #[error(
+        "function `{name}` is declared with {expected} parameter(s), but defined with {found} parameter(s)"
+    )]
+    FnArityMismatch {
+        /// The declaration in the definition file.
+        definition: Symbol,
+        /// The function in the script.
+        function: Symbol,
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error("function `{name}` with {arity} parameter(s) is declared, but the script does not define it")]
+    MissingFnDefinition {
+        /// The declaration in the definition file.
+        definition: Symbol,
+        name: String,
+        arity: usize,
+    },
+    #[error("function `{name}` with {arity} parameter(s) is not declared in the definition file of the script")]
+    UndeclaredFn {
+        /// The function in the script.
+        function: Symbol,
+        name: String,
+        arity: usize,
+    },
+    #[error("cyclic module import")]
+    CyclicImport {
+        import: Symbol,
+        /// The other imports that are part of the cycle
+        /// in import order.
+        cycle: Vec<Symbol>,
+    },
+    #[error(
+        "`{name}` is deprecated{}",
+        if reason.is_empty() {
+            String::new()
+        } else {
+            format!(": {reason}")
+        }
+    )]
+    Deprecated {
+        /// The reference or operation using the deprecated item.
+        reference_symbol: Symbol,
+        /// The deprecated declaration, operators have no symbols.
+        target: Option<Symbol>,
+        name: String,
+        reason: String,
+    },
     #[error("nested functions are not allowed")]
     NestedFunction { function: Symbol },
+    #[error("closure created in a loop captures the loop variable `{name}`")]
+    ClosureCapturesLoopVariable {
+        /// The first reference to the loop variable inside the closure.
+        reference_symbol: Symbol,
+        /// The declaration of the loop variable.
+        loop_variable: Symbol,
+        name: String,
+    },
+    #[error("division by zero")]
+    DivisionByZero {
+        /// The division or remainder operation.
+        operation: Symbol,
+    },
+    #[error("function pointer expects {expected} argument(s), but {found} were given")]
+    FnPointerArityMismatch {
+        /// The call of the closure or function pointer.
+        call: Symbol,
+        expected: usize,
+        found: usize,
+    },
+    #[error("no operator `{name}` for ({lhs_ty}, {rhs_ty})")]
+    NoMatchingOperator {
+        /// The binary operation.
+        operation: Symbol,
+        name: String,
+        lhs_ty: String,
+        rhs_ty: String,
+    },
+    #[error("this condition is always {value}")]
+    ConstantCondition { condition: Symbol, value: bool },
+    #[error("duplicate switch case")]
+    DuplicateSwitchArm {
+        /// The pattern of the duplicate arm.
+        duplicate_symbol: Symbol,
+        /// The pattern of the first arm with the same value.
+        existing_symbol: Symbol,
+    },
+    #[error("unreachable switch case after the default case")]
+    UnreachableSwitchArm {
+        /// The pattern of the unreachable arm.
+        arm_symbol: Symbol,
+        /// The `_` pattern of the default case.
+        default_symbol: Symbol,
+    },
+    #[error("property `{name}` does not exist in the object map")]
+    AbsentField {
+        /// The reference to the field.
+        reference_symbol: Symbol,
+        name: String,
+    },
+    #[error("duplicate property `{name}` in object map")]
+    DuplicateObjectKey {
+        object: Symbol,
+        name: String,
+        duplicate_range: TextRange,
+        existing_range: TextRange,
+    },
 }
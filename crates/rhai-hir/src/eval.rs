@@ -1,10 +1,19 @@
-#[derive(Debug, Clone)]
+use crate::IndexMap;
+use core::cmp::Ordering;
+use rhai_rowan::syntax::SyntaxKind;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Value {
     Int(i64),
     Float(f64),
     Bool(bool),
     String(String),
     Char(char),
+    Array(Vec<Value>),
+    Map(IndexMap<String, Value>),
+    /// The unit value `()`.
+    Unit,
     Unknown,
 }
 
@@ -16,6 +25,30 @@ impl core::fmt::Display for Value {
             Value::Bool(v) => v.fmt(f),
             Value::String(v) => write!(f, r#""{v}""#),
             Value::Char(v) => write!(f, "'{v}'"),
+            Value::Array(values) => {
+                write!(f, "[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    v.fmt(f)?;
+                }
+                write!(f, "]")
+            }
+            Value::Map(fields) => {
+                write!(f, "#{{")?;
+                for (i, (k, v)) in fields.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, " {k}: {v}")?;
+                }
+                if !fields.is_empty() {
+                    write!(f, " ")?;
+                }
+                write!(f, "}}")
+            }
+            Value::Unit => "()".fmt(f),
             Value::Unknown => "UNKNOWN VALUE".fmt(f),
         }
     }
@@ -107,6 +140,40 @@ impl Value {
         }
     }
 
+    /// Returns `true` if the value is [`Array`].
+    ///
+    /// [`Array`]: Value::Array
+    #[must_use]
+    pub fn is_array(&self) -> bool {
+        matches!(self, Self::Array(..))
+    }
+
+    #[must_use]
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        if let Self::Array(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the value is [`Map`].
+    ///
+    /// [`Map`]: Value::Map
+    #[must_use]
+    pub fn is_map(&self) -> bool {
+        matches!(self, Self::Map(..))
+    }
+
+    #[must_use]
+    pub fn as_map(&self) -> Option<&IndexMap<String, Value>> {
+        if let Self::Map(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
     /// Returns `true` if the value is [`Unknown`].
     ///
     /// [`Unknown`]: Value::Unknown
@@ -121,3 +188,142 @@ impl Default for Value {
         Self::Unknown
     }
 }
+
+impl Value {
+    /// Whether the value and all its nested values are known.
+    #[must_use]
+    pub fn is_known(&self) -> bool {
+        match self {
+            Value::Array(values) => values.iter().all(Value::is_known),
+            Value::Map(fields) => fields.values().all(Value::is_known),
+            Value::Unknown => false,
+            _ => true,
+        }
+    }
+
+    /// The text of the value when it is converted to a string,
+    /// e.g. during concatenation or in string templates.
+    #[must_use]
+    pub fn to_text(&self) -> Option<String> {
+        match self {
+            Value::String(s) => Some(s.clone()),
+            Value::Char(c) => Some(c.to_string()),
+            v if v.is_known() => Some(v.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Apply a unary operator to a constant value.
+    #[must_use]
+    pub fn unary(op: SyntaxKind, rhs: &Value) -> Value {
+        match (op, rhs) {
+            (SyntaxKind::OP_ADD, Value::Int(_) | Value::Float(_)) => rhs.clone(),
+            (SyntaxKind::OP_SUB, Value::Int(v)) => {
+                v.checked_neg().map_or(Value::Unknown, Value::Int)
+            }
+            (SyntaxKind::OP_SUB, Value::Float(v)) => Value::Float(-v),
+            (SyntaxKind::OP_NOT, Value::Bool(v)) => Value::Bool(!v),
+            _ => Value::Unknown,
+        }
+    }
+
+    /// Apply a binary operator to constant values.
+    ///
+    /// Operations that would fail at runtime
+    /// (e.g. integer overflow or division by zero) result in [`Value::Unknown`].
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn binary(lhs: &Value, op: SyntaxKind, rhs: &Value) -> Value {
+        if lhs.is_unknown() {
+            return Value::Unknown;
+        }
+
+        match op {
+            SyntaxKind::OP_NULL_OR if matches!(lhs, Value::Unit) => return rhs.clone(),
+            SyntaxKind::OP_NULL_OR => return lhs.clone(),
+            SyntaxKind::OP_BOOL_AND if matches!(lhs, Value::Bool(false)) => {
+                return Value::Bool(false)
+            }
+            SyntaxKind::OP_BOOL_OR if matches!(lhs, Value::Bool(true)) => return Value::Bool(true),
+            _ => {}
+        }
+
+        if rhs.is_unknown() {
+            return Value::Unknown;
+        }
+
+        match (lhs, rhs) {
+            (Value::Int(l), Value::Int(r)) => int_op(*l, op, *r),
+            (Value::Float(l), Value::Float(r)) => float_op(*l, op, *r),
+            (Value::Int(l), Value::Float(r)) => float_op(*l as f64, op, *r),
+            (Value::Float(l), Value::Int(r)) => float_op(*l, op, *r as f64),
+            (Value::Bool(l), Value::Bool(r)) => match op {
+                SyntaxKind::OP_BOOL_AND | SyntaxKind::OP_BIT_AND => Value::Bool(*l && *r),
+                SyntaxKind::OP_BOOL_OR | SyntaxKind::OP_BIT_OR => Value::Bool(*l || *r),
+                SyntaxKind::OP_BIT_XOR => Value::Bool(l ^ r),
+                _ => compare(op, l.cmp(r)),
+            },
+            (Value::String(_) | Value::Char(_), Value::String(_) | Value::Char(_))
+                if op != SyntaxKind::OP_ADD =>
+            {
+                match (lhs.to_text(), rhs.to_text()) {
+                    (Some(l), Some(r)) => compare(op, l.cmp(&r)),
+                    _ => Value::Unknown,
+                }
+            }
+            (Value::String(_), _) | (_, Value::String(_)) if op == SyntaxKind::OP_ADD => {
+                match (lhs.to_text(), rhs.to_text()) {
+                    (Some(l), Some(r)) => Value::String(l + &r),
+                    _ => Value::Unknown,
+                }
+            }
+            (Value::Array(l), Value::Array(r)) if op == SyntaxKind::OP_ADD => {
+                Value::Array(l.iter().chain(r).cloned().collect())
+            }
+            _ => Value::Unknown,
+        }
+    }
+}
+
+fn int_op(l: i64, op: SyntaxKind, r: i64) -> Value {
+    let v = match op {
+        SyntaxKind::OP_ADD => l.checked_add(r),
+        SyntaxKind::OP_SUB => l.checked_sub(r),
+        SyntaxKind::OP_MUL => l.checked_mul(r),
+        SyntaxKind::OP_DIV => l.checked_div(r),
+        SyntaxKind::OP_MOD => l.checked_rem(r),
+        SyntaxKind::OP_POW => u32::try_from(r).ok().and_then(|r| l.checked_pow(r)),
+        SyntaxKind::OP_SHIFT_LEFT => u32::try_from(r).ok().and_then(|r| l.checked_shl(r)),
+        SyntaxKind::OP_SHIFT_RIGHT => u32::try_from(r).ok().and_then(|r| l.checked_shr(r)),
+        SyntaxKind::OP_BIT_AND => Some(l & r),
+        SyntaxKind::OP_BIT_OR => Some(l | r),
+        SyntaxKind::OP_BIT_XOR => Some(l ^ r),
+        _ => return compare(op, l.cmp(&r)),
+    };
+
+    v.map_or(Value::Unknown, Value::Int)
+}
+
+fn float_op(l: f64, op: SyntaxKind, r: f64) -> Value {
+    match op {
+        SyntaxKind::OP_ADD => Value::Float(l + r),
+        SyntaxKind::OP_SUB => Value::Float(l - r),
+        SyntaxKind::OP_MUL => Value::Float(l * r),
+        SyntaxKind::OP_DIV => Value::Float(l / r),
+        SyntaxKind::OP_MOD => Value::Float(l % r),
+        SyntaxKind::OP_POW => Value::Float(l.powf(r)),
+        _ => l.partial_cmp(&r).map_or(Value::Unknown, |o| compare(op, o)),
+    }
+}
+
+fn compare(op: SyntaxKind, ordering: Ordering) -> Value {
+    match op {
+        SyntaxKind::OP_EQ => Value::Bool(ordering.is_eq()),
+        SyntaxKind::OP_NOT_EQ => Value::Bool(ordering.is_ne()),
+        SyntaxKind::OP_LT => Value::Bool(ordering.is_lt()),
+        SyntaxKind::OP_LT_EQ => Value::Bool(ordering.is_le()),
+        SyntaxKind::OP_GT => Value::Bool(ordering.is_gt()),
+        SyntaxKind::OP_GT_EQ => Value::Bool(ordering.is_ge()),
+        _ => Value::Unknown,
+    }
+}
@@ -0,0 +1,214 @@
+//! Stable JSON-compatible representation of the HIR.
+//!
+//! Unlike the [textual dump](crate::fmt), this format is intended
+//! to be consumed by external tools.
+//!
+//! # Versioning
+//!
+//! The format is versioned by [`EXPORT_VERSION`], fields might be added
+//! without changing the version, but existing fields are never removed or changed.
+//!
+//! # Identifiers
+//!
+//! Sources, modules, scopes and symbols are identified by numeric IDs
+//! that are unique for each item kind within a single export.
+//! The IDs are not stable across exports.
+
+use crate::{
+    module::ModuleKind,
+    scope::ScopeParent,
+    source::{SourceInfo, SourceKind},
+    symbol::ReferenceTarget,
+    Hir,
+};
+use rhai_rowan::TextRange;
+use serde::{Deserialize, Serialize};
+use slotmap::Key;
+use url::Url;
+
+/// The current version of the export format.
+pub const EXPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HirExport {
+    pub version: u32,
+    pub sources: Vec<SourceExport>,
+    pub modules: Vec<ModuleExport>,
+    pub scopes: Vec<ScopeExport>,
+    pub symbols: Vec<SymbolExport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceExport {
+    pub id: u64,
+    pub url: Url,
+    /// Either `script` or `def`.
+    pub kind: String,
+    pub module: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleExport {
+    pub id: u64,
+    /// Either `static`, `inline` or `url`.
+    pub kind: String,
+    pub url: Option<Url>,
+    pub docs: String,
+    pub scope: u64,
+    pub sources: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeExport {
+    pub id: u64,
+    pub source: Option<u64>,
+    pub range: Option<RangeExport>,
+    pub parent_scope: Option<u64>,
+    pub parent_symbol: Option<u64>,
+    pub symbols: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolExport {
+    pub id: u64,
+    /// The kind of the symbol, e.g. `Fn` or `Decl`.
+    pub kind: String,
+    pub name: Option<String>,
+    pub source: Option<u64>,
+    pub range: Option<RangeExport>,
+    pub selection_range: Option<RangeExport>,
+    pub docs: Option<String>,
+    pub export: bool,
+    /// The formatted type of the symbol.
+    pub ty: String,
+    pub parent_scope: Option<u64>,
+    pub target_symbol: Option<u64>,
+    pub target_module: Option<u64>,
+}
+
+/// A range of UTF-8 byte offsets.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeExport {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl From<TextRange> for RangeExport {
+    fn from(range: TextRange) -> Self {
+        Self {
+            start: range.start().into(),
+            end: range.end().into(),
+        }
+    }
+}
+
+impl Hir {
+    /// Create a stable representation of the HIR.
+    #[must_use]
+    pub fn export(&self) -> HirExport {
+        HirExport {
+            version: EXPORT_VERSION,
+            sources: self
+                .sources()
+                .map(|(source, data)| SourceExport {
+                    id: id(source),
+                    url: data.url.clone(),
+                    kind: match data.kind {
+                        SourceKind::Script => "script",
+                        SourceKind::Def => "def",
+                    }
+                    .into(),
+                    module: non_null(data.module),
+                })
+                .collect(),
+            modules: self
+                .modules()
+                .map(|(module, data)| ModuleExport {
+                    id: id(module),
+                    kind: match &data.kind {
+                        ModuleKind::Static => "static",
+                        ModuleKind::Inline => "inline",
+                        ModuleKind::Url(_) => "url",
+                    }
+                    .into(),
+                    url: data.url().cloned(),
+                    docs: data.docs.clone(),
+                    scope: id(data.scope),
+                    sources: data.sources.iter().copied().map(id).collect(),
+                })
+                .collect(),
+            scopes: self
+                .scopes()
+                .map(|(scope, data)| {
+                    let mut symbols = data.iter_symbols().map(id).collect::<Vec<_>>();
+                    // Hoisted symbols have no stable order.
+                    symbols.sort_unstable();
+
+                    ScopeExport {
+                        id: id(scope),
+                        source: source_of(&data.source),
+                        range: data.source.text_range.map(Into::into),
+                        parent_scope: match data.parent {
+                            Some(ScopeParent::Scope(s)) => Some(id(s)),
+                            _ => None,
+                        },
+                        parent_symbol: match data.parent {
+                            Some(ScopeParent::Symbol(s)) => Some(id(s)),
+                            _ => None,
+                        },
+                        symbols,
+                    }
+                })
+                .collect(),
+            symbols: self
+                .symbols()
+                .map(|(symbol, data)| {
+                    let target = data.target();
+
+                    SymbolExport {
+                        id: id(symbol),
+                        kind: Into::<&'static str>::into(&data.kind).into(),
+                        name: data.name(self).map(ToString::to_string),
+                        source: source_of(&data.source),
+                        range: data.source.text_range.map(Into::into),
+                        selection_range: data.source.selection_text_range.map(Into::into),
+                        docs: data.docs().map(ToString::to_string),
+                        export: data.export,
+                        ty: data.ty.fmt(self).to_string(),
+                        parent_scope: non_null(data.parent_scope),
+                        target_symbol: match target {
+                            Some(ReferenceTarget::Symbol(s)) => Some(id(s)),
+                            _ => None,
+                        },
+                        target_module: match target {
+                            Some(ReferenceTarget::Module(m)) => Some(id(m)),
+                            _ => None,
+                        },
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+fn id(key: impl Key) -> u64 {
+    key.data().as_ffi()
+}
+
+fn non_null(key: impl Key) -> Option<u64> {
+    if key.is_null() {
+        None
+    } else {
+        Some(id(key))
+    }
+}
+
+fn source_of(info: &SourceInfo) -> Option<u64> {
+    info.source.map(id)
+}
@@ -4,7 +4,8 @@
 //!
 //! The output format is designed to be somewhat
 //! readable, but is not intended to be too pretty.
-//! It is not stable and should not be relied on in any way.
+//! It is not stable and should not be relied on in any way,
+//! use the [export](crate::export) format for tooling instead.
 //!
 //! ## Slots
 //!
@@ -581,6 +582,13 @@ impl<'h> HirFmt<'h> {
                     windent!(self, f, "}}")?;
                 }
             }
+            SymbolKind::Field(field) => {
+                write!(f, " {}", field.name)?;
+
+                if !field.docs.is_empty() {
+                    write!(f, " docs: {:?}", field.docs)?;
+                }
+            }
             SymbolKind::Call(call) => {
                 let indented = self.incr_indent();
                 writeln!(f)?;
@@ -607,6 +615,16 @@ impl<'h> HirFmt<'h> {
                 }
             }
             SymbolKind::Closure(closure) => {
+                if !closure.captures.is_empty() {
+                    let names: Vec<&str> = closure
+                        .captures
+                        .iter()
+                        .filter_map(|&sym| self.hir[sym].name(self.hir))
+                        .collect();
+
+                    write!(f, " captures({})", names.join(", "))?;
+                }
+
                 write!(f, " ")?;
                 self.fmt_scope(f, closure.scope)?;
             }
@@ -742,7 +760,7 @@ impl<'h> HirFmt<'h> {
                     }
 
                     windentln!(indented, f, "then")?;
-                    match arm.pat_expr {
+                    match arm.value_expr {
                         Some(c) => {
                             indented.fmt_symbol(f, c)?;
                         }
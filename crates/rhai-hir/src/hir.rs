@@ -1,5 +1,7 @@
 mod add;
 mod errors;
+mod eval;
+mod index;
 mod query;
 mod remove;
 mod resolve;
@@ -8,19 +10,29 @@ use core::ops;
 use std::sync::Arc;
 
 use crate::{
+    builtin::StdPackage,
     module::{ModuleData, ModuleResolver, DefaultModuleResolver},
     scope::ScopeData,
     source::{Source, SourceData},
     symbol::*,
     ty::{Type, TypeData},
-    Module, Scope,
+    HashMap, HashSet, Module, Scope,
 };
 
 use rhai_rowan::syntax::SyntaxNode;
-use slotmap::{Key, SlotMap};
+use serde::{Deserialize, Deserializer, Serialize};
+use slotmap::{Key, SecondaryMap, SlotMap};
 use url::Url;
 
-#[derive(Clone)]
+use self::index::SourceIndex;
+
+/// The HIR can be serialized and deserialized as a whole,
+/// e.g. for caching.
+///
+/// Positional indices are rebuilt after deserialization,
+/// the module resolver is always reset to the default one
+/// and the host functions that take function names are cleared.
+#[derive(Clone, Serialize)]
 pub struct Hir {
     pub(crate) static_module: Module,
     pub(crate) virtual_source: Source,
@@ -30,7 +42,76 @@ pub struct Hir {
     pub(crate) sources: SlotMap<Source, SourceData>,
     pub(crate) types: SlotMap<Type, TypeData>,
     pub(crate) builtin_types: BuiltinTypes,
-    pub(crate) module_resolver: Arc<dyn ModuleResolver>
+    pub(crate) std_packages: Vec<StdPackage>,
+    #[serde(skip)]
+    pub(crate) source_indices: SecondaryMap<Source, SourceIndex>,
+    /// Other modules that resolved symbols of each source refer to.
+    #[serde(skip)]
+    pub(crate) source_dependencies: SecondaryMap<Source, HashSet<Module>>,
+    /// The reverse of `source_dependencies`.
+    #[serde(skip)]
+    pub(crate) module_dependents: HashMap<Module, HashSet<Source>>,
+    /// Sources with imports of modules that did not exist
+    /// when the imports were resolved, by the URLs of the modules.
+    #[serde(skip)]
+    pub(crate) unresolved_imports: HashMap<Url, HashSet<Source>>,
+    /// Removed sources and the sources that depended on them,
+    /// these are resolved again by the next call to [`Hir::resolve_sources`].
+    #[serde(skip)]
+    pub(crate) pending_sources: HashSet<Source>,
+    #[serde(skip)]
+    pub(crate) module_resolver: Arc<dyn ModuleResolver>,
+    #[serde(skip)]
+    pub(crate) fn_name_functions: Vec<String>,
+}
+
+impl<'de> Deserialize<'de> for Hir {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct HirData {
+            static_module: Module,
+            virtual_source: Source,
+            modules: SlotMap<Module, ModuleData>,
+            scopes: SlotMap<Scope, ScopeData>,
+            symbols: SlotMap<Symbol, SymbolData>,
+            sources: SlotMap<Source, SourceData>,
+            types: SlotMap<Type, TypeData>,
+            builtin_types: BuiltinTypes,
+            std_packages: Vec<StdPackage>,
+        }
+
+        let data = HirData::deserialize(deserializer)?;
+
+        let mut hir = Self {
+            static_module: data.static_module,
+            virtual_source: data.virtual_source,
+            modules: data.modules,
+            scopes: data.scopes,
+            symbols: data.symbols,
+            sources: data.sources,
+            types: data.types,
+            builtin_types: data.builtin_types,
+            std_packages: data.std_packages,
+            source_indices: Default::default(),
+            source_dependencies: Default::default(),
+            module_dependents: Default::default(),
+            unresolved_imports: Default::default(),
+            pending_sources: Default::default(),
+            module_resolver: Arc::new(DefaultModuleResolver),
+            fn_name_functions: Vec::new(),
+        };
+
+        for source in hir.sources.keys().collect::<Vec<_>>() {
+            hir.index_source(source);
+        }
+
+        hir.collect_source_dependencies(None);
+
+        Ok(hir)
+    }
 }
 
 impl Default for Hir {
@@ -44,7 +125,14 @@ impl Default for Hir {
             sources: Default::default(),
             types: Default::default(),
             builtin_types: BuiltinTypes::uninit(),
-            module_resolver: Arc::new(DefaultModuleResolver)
+            std_packages: Vec::new(),
+            source_indices: Default::default(),
+            source_dependencies: Default::default(),
+            module_dependents: Default::default(),
+            unresolved_imports: Default::default(),
+            pending_sources: Default::default(),
+            module_resolver: Arc::new(DefaultModuleResolver),
+            fn_name_functions: Vec::new(),
         };
         this.prepare();
         this
@@ -62,6 +150,15 @@ impl Hir {
     pub fn set_import_resolver(&mut self, resolver: impl ModuleResolver + 'static) {
         self.module_resolver = Arc::new(resolver);
     }
+
+    /// Set the names of host functions that take the name of a function
+    /// as their first argument, e.g. `call_fn`.
+    ///
+    /// String literals passed to these functions are references to the
+    /// named functions, the same way as in `Fn("name")`.
+    pub fn set_fn_name_functions(&mut self, names: impl IntoIterator<Item = String>) {
+        self.fn_name_functions = names.into_iter().collect();
+    }
 }
 
 impl Hir {
@@ -71,6 +168,11 @@ impl Hir {
         self.modules.clear();
         self.sources.clear();
         self.types.clear();
+        self.source_indices.clear();
+        self.source_dependencies.clear();
+        self.module_dependents.clear();
+        self.unresolved_imports.clear();
+        self.pending_sources.clear();
         self.builtin_types = BuiltinTypes::uninit();
         self.static_module = Module::null();
         self.prepare();
@@ -150,6 +252,7 @@ impl Hir {
         self.ensure_static_module();
         self.ensure_virtual_source();
         self.ensure_builtin_types();
+        self.ensure_std_packages();
     }
 }
 
@@ -226,7 +329,7 @@ impl ops::Index<Type> for Hir {
 /// but always exist in the HIR and cannot be removed.
 ///
 /// This struct keeps track of their keys.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct BuiltinTypes {
     pub module: Type,
     pub int: Type,
@@ -2,9 +2,9 @@ use super::*;
 use crate::{
     module::ModuleKind,
     source::SourceInfo,
-    ty::{Array, Object},
+    ty::{Array, Function, Object},
     util::script_url,
-    IndexSet,
+    HashSet, IndexMap, IndexSet,
 };
 use rhai_rowan::{
     ast::{self, AstNode, Def, DefStmt, RhaiDef},
@@ -206,6 +206,16 @@ impl Hir {
                 scope.add_symbol(self, symbol, true);
             }
             Def::Fn(expr) => {
+                let type_params = expr
+                    .type_param_list()
+                    .map(|list| {
+                        list.params()
+                            .filter_map(|param| param.ident_token())
+                            .map(|ident| ident.text().trim().to_string())
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+
                 let fn_scope = self.scopes.insert(ScopeData {
                     source: SourceInfo {
                         source: Some(source),
@@ -217,7 +227,9 @@ impl Hir {
 
                 if let Some(param_list) = expr.typed_param_list() {
                     for param in param_list.params() {
-                        let param_ty = param.ty().map(|t| self.add_type(source, None, &t));
+                        let param_ty = param
+                            .ty()
+                            .map(|t| self.add_type_with_params(source, None, &t, &type_params));
                         let symbol = self.add_symbol(SymbolData {
                             export: false,
                             parent_scope: Scope::default(),
@@ -244,7 +256,7 @@ impl Hir {
                 }
 
                 let ret_ty = expr.ret_ty().map_or(self.builtin_types.unknown, |t| {
-                    self.add_type(source, None, &t)
+                    self.add_type_with_params(source, None, &t, &type_params)
                 });
 
                 let symbol = self.add_symbol(SymbolData {
@@ -267,6 +279,7 @@ impl Hir {
                         setter: expr.has_kw_set(),
                         is_def: true,
                         ret_ty,
+                        type_params,
                         ..FnSymbol::default()
                     }),
                     ty: self.builtin_types.unknown,
@@ -438,22 +451,40 @@ impl Hir {
         source: Source,
         selection_text_range: Option<TextRange>,
         ty: &ast::Type,
+    ) -> Type {
+        self.add_type_with_params(source, selection_text_range, ty, &[])
+    }
+
+    /// Add a type where the given names are type variables
+    /// instead of references to other types.
+    fn add_type_with_params(
+        &mut self,
+        source: Source,
+        selection_text_range: Option<TextRange>,
+        ty: &ast::Type,
+        type_params: &[String],
     ) -> Type {
         match &ty {
-            ast::Type::Ident(ident) => self.types.insert(TypeData {
-                source: SourceInfo {
-                    source: Some(source),
-                    text_range: Some(ty.syntax().text_range()),
-                    selection_text_range,
-                },
-                protected: false,
-                kind: TypeKind::Unresolved(
-                    ident
-                        .ident_token()
-                        .map(|t| t.text().trim().to_string())
-                        .unwrap_or_default(),
-                ),
-            }),
+            ast::Type::Ident(ident) => {
+                let name = ident
+                    .ident_token()
+                    .map(|t| t.text().trim().to_string())
+                    .unwrap_or_default();
+
+                self.types.insert(TypeData {
+                    source: SourceInfo {
+                        source: Some(source),
+                        text_range: Some(ty.syntax().text_range()),
+                        selection_text_range,
+                    },
+                    protected: false,
+                    kind: if type_params.contains(&name) {
+                        TypeKind::Var(name)
+                    } else {
+                        TypeKind::Unresolved(name)
+                    },
+                })
+            }
             ast::Type::Lit(lit) => match &lit.lit() {
                 Some(l) => match l.lit_token() {
                     Some(t) => match t.kind() {
@@ -475,24 +506,63 @@ impl Hir {
                 None => self.builtin_types.unknown,
             },
             ast::Type::Object(o) => {
-                let fields = o
-                    .fields()
-                    .map(|field| {
-                        let name = if let Some(lit) = field.name_lit() {
-                            value_of_lit(lit).to_string()
-                        } else if let Some(ident) = field.name_ident() {
-                            ident.text().to_string()
-                        } else {
-                            String::new()
-                        };
-
-                        if let Some(ty) = field.ty() {
-                            (name, self.add_type(source, None, &ty))
-                        } else {
-                            (name, self.builtin_types.unknown)
+                let mut fields = IndexMap::default();
+                let mut field_symbols = IndexMap::default();
+                let mut docs = IndexMap::default();
+
+                for field in o.fields() {
+                    let (name, name_range) = if let Some(ident) = field.name_ident() {
+                        (ident.text().to_string(), Some(ident.text_range()))
+                    } else if let Some(lit_str) = field.name_lit_str() {
+                        let (name, range) = field_name(&lit_str);
+                        (name, Some(range))
+                    } else if let Some(lit) = field.name_lit() {
+                        match lit.lit_token() {
+                            Some(token) => {
+                                let (name, range) = field_name(&token);
+                                (name, Some(range))
+                            }
+                            None => (value_of_lit(lit).to_string(), None),
                         }
-                    })
-                    .collect();
+                    } else {
+                        (String::new(), None)
+                    };
+
+                    let field_ty = if let Some(ty) = field.ty() {
+                        self.add_type_with_params(source, None, &ty, type_params)
+                    } else {
+                        self.builtin_types.unknown
+                    };
+
+                    let field_docs = field.docs_content();
+
+                    let field_symbol = self.add_symbol(SymbolData {
+                        export: false,
+                        parent_scope: Scope::default(),
+                        source: SourceInfo {
+                            source: Some(source),
+                            text_range: field.syntax().text_range().into(),
+                            selection_text_range: name_range,
+                        },
+                        kind: SymbolKind::Field(FieldSymbol {
+                            name: name.clone(),
+                            docs: field_docs.clone(),
+                            value: None,
+                            references: HashSet::default(),
+                        }),
+                        ty: field_ty,
+                    });
+                    self[self[source].module]
+                        .scope
+                        .add_symbol(self, field_symbol, false);
+
+                    if !field_docs.is_empty() {
+                        docs.insert(name.clone(), field_docs);
+                    }
+
+                    field_symbols.insert(name.clone(), field_symbol);
+                    fields.insert(name, field_ty);
+                }
 
                 self.types.insert(TypeData {
                     source: SourceInfo {
@@ -501,12 +571,16 @@ impl Hir {
                         selection_text_range,
                     },
                     protected: false,
-                    kind: TypeKind::Object(Object { fields }),
+                    kind: TypeKind::Object(Object {
+                        fields,
+                        field_symbols,
+                        docs,
+                    }),
                 })
             }
             ast::Type::Array(arr) => {
                 let ty = if let Some(ty) = arr.fist_ty() {
-                    self.add_type(source, None, &ty)
+                    self.add_type_with_params(source, None, &ty, type_params)
                 } else {
                     self.builtin_types.unknown
                 };
@@ -524,9 +598,14 @@ impl Hir {
             ast::Type::Tuple(tuple) => {
                 let types = tuple
                     .types()
-                    .map(|ty| self.add_type(source, None, &ty))
+                    .map(|ty| self.add_type_with_params(source, None, &ty, type_params))
                     .collect::<Vec<_>>();
 
+                // `()` is the unit type.
+                if types.is_empty() {
+                    return self.builtin_types.void;
+                }
+
                 self.types.insert(TypeData {
                     source: SourceInfo {
                         source: Some(source),
@@ -537,6 +616,61 @@ impl Hir {
                     kind: TypeKind::Tuple(types),
                 })
             }
+            ast::Type::Fn(f) => {
+                let params = f
+                    .param_types()
+                    .map(|ty| {
+                        (
+                            String::new(),
+                            self.add_type_with_params(source, None, &ty, type_params),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                let ret = f.ret_ty().map_or(self.builtin_types.unknown, |ty| {
+                    self.add_type_with_params(source, None, &ty, type_params)
+                });
+
+                self.types.insert(TypeData {
+                    source: SourceInfo {
+                        source: Some(source),
+                        text_range: Some(f.syntax().text_range()),
+                        selection_text_range,
+                    },
+                    protected: false,
+                    kind: TypeKind::Fn(Function {
+                        is_closure: true,
+                        type_params: Vec::new(),
+                        params,
+                        ret,
+                    }),
+                })
+            }
+            ast::Type::Union(union) => {
+                let mut tys = IndexSet::default();
+
+                for ty in [union.ty_left(), union.ty_right()].into_iter().flatten() {
+                    let ty = self.add_type_with_params(source, None, &ty, type_params);
+
+                    // Nested unions are flattened.
+                    if self[ty].kind.is_union() {
+                        let nested = self.types.remove(ty).unwrap();
+                        tys.extend(nested.kind.as_union().unwrap().iter().copied());
+                    } else {
+                        tys.insert(ty);
+                    }
+                }
+
+                self.types.insert(TypeData {
+                    source: SourceInfo {
+                        source: Some(source),
+                        text_range: Some(ty.syntax().text_range()),
+                        selection_text_range,
+                    },
+                    protected: false,
+                    kind: TypeKind::Union(tys),
+                })
+            }
             ast::Type::Unknown(_) => self.builtin_types.unknown,
         }
     }
@@ -7,8 +7,8 @@ use crate::{
     TypeKind,
 };
 use rhai_rowan::{
-    ast::{AstNode, Lit, Rhai, RhaiDef},
-    syntax::SyntaxKind,
+    ast::{AstNode, Lit, LitStrTemplate, LitStrTemplateSegment, Rhai, RhaiDef},
+    syntax::{SyntaxKind, SyntaxToken},
     util::unescape,
     TextRange, TextSize,
 };
@@ -30,6 +30,7 @@ impl Hir {
             });
 
             self.add_script(source, &rhai);
+            self.index_source(source);
         }
 
         if let Some(def) = RhaiDef::cast(syntax.clone()) {
@@ -40,6 +41,7 @@ impl Hir {
             });
 
             self.add_def(source, &def);
+            self.index_source(source);
         }
     }
 }
@@ -330,9 +332,64 @@ fn value_of_lit(lit: Lit) -> Value {
             _ => Value::Unknown,
         }
     } else {
-        // It's a string template literal
-        // FIXME: we know its content if it has
+        // It's a string template literal,
+        // we only know its content if it has
         // no code interpolations.
-        Value::String(String::new())
+        match lit.lit_str_template() {
+            Some(template) if template.interpolations().next().is_none() => {
+                Value::String(template_parts(&template).concat())
+            }
+            _ => Value::String(String::new()),
+        }
+    }
+}
+
+/// Unescaped string segments of a template literal,
+/// there is always one more segment than interpolations.
+fn template_parts(template: &LitStrTemplate) -> Vec<String> {
+    let mut parts = vec![String::new()];
+
+    for segment in template.segments() {
+        match segment {
+            LitStrTemplateSegment::LitStr(token) => {
+                let text = token.text();
+                let text = text.strip_prefix('`').unwrap_or(text);
+                let text = text.strip_suffix('`').unwrap_or(text);
+                parts.last_mut().unwrap().push_str(&unescape(text, '`').0);
+            }
+            LitStrTemplateSegment::Interpolation(_) => parts.push(String::new()),
+        }
     }
+
+    parts
+}
+
+/// The name of an object map field and the range of the name.
+///
+/// Names given as string literals are unescaped,
+/// and the range excludes the quotes.
+fn field_name(token: &SyntaxToken) -> (String, TextRange) {
+    let text = token.text();
+    let range = token.text_range();
+
+    if token.kind() != SyntaxKind::LIT_STR {
+        return (text.to_string(), range);
+    }
+
+    let quote = if text.starts_with('`') { '`' } else { '"' };
+
+    let Some(inner) = text
+        .strip_prefix(quote)
+        .and_then(|text| text.strip_suffix(quote))
+    else {
+        return (text.to_string(), range);
+    };
+
+    (
+        unescape(inner, quote).0,
+        TextRange::new(
+            range.start() + TextSize::from(1),
+            range.end() - TextSize::from(1),
+        ),
+    )
 }
@@ -1,4 +1,4 @@
-use crate::{eval::Value, source::SourceInfo};
+use crate::{eval::Value, source::SourceInfo, HashSet, IndexMap, IndexSet};
 use rhai_rowan::{
     ast::{ExportTarget, Expr, Item, Rhai, Stmt},
     parser::Parser,
@@ -125,7 +125,7 @@ impl Hir {
         match expr {
             Expr::Ident(expr) => {
                 let symbol = self.add_symbol(SymbolData {
-                    export: can_export,
+                    export: false,
                     source: SourceInfo {
                         source: Some(source),
                         text_range: expr.syntax().text_range().into(),
@@ -173,7 +173,7 @@ impl Hir {
                         segments: segments
                             .map(|s| {
                                 let symbol = self.add_symbol(SymbolData {
-                                    export: can_export,
+                                    export: false,
                                     source: SourceInfo {
                                         source: Some(source),
                                         text_range: s.text_range().into(),
@@ -201,17 +201,30 @@ impl Hir {
                 Some(sym)
             }
             Expr::Lit(expr) => {
+                // Strings can name functions, e.g. in `Fn("name")`.
+                let selection_text_range = expr
+                    .lit()
+                    .and_then(|l| l.lit_token())
+                    .filter(|t| t.kind() == SyntaxKind::LIT_STR)
+                    .map(|t| field_name(&t).1);
+
                 let symbol = self.add_symbol(SymbolData {
                     export: false,
                     parent_scope: Scope::default(),
                     source: SourceInfo {
                         source: Some(source),
                         text_range: expr.syntax().text_range().into(),
-                        selection_text_range: None,
+                        selection_text_range,
                     },
                     kind: SymbolKind::Lit(LitSymbol {
                         value: expr.lit().map_or(Value::Unknown, value_of_lit),
+                        template_parts: expr
+                            .lit()
+                            .and_then(|l| l.lit_str_template())
+                            .map(|t| template_parts(&t))
+                            .unwrap_or_default(),
                         interpolated_scopes: Vec::default(),
+                        targets: Vec::new(),
                     }),
                     ty: self.builtin_types.unknown,
                 });
@@ -257,7 +270,7 @@ impl Hir {
                 expr.ident_token(),
                 &expr.syntax(),
                 false,
-                can_export,
+                false,
                 self.builtin_types.unknown,
             )
             .into(),
@@ -269,7 +282,7 @@ impl Hir {
                 expr.ident_token(),
                 &expr.syntax(),
                 true,
-                can_export,
+                false,
                 self.builtin_types.unknown,
             )
             .into(),
@@ -382,6 +395,7 @@ impl Hir {
                         lhs,
                         op,
                         rhs,
+                        operator: None,
                     }),
                     ty: self.builtin_types.unknown,
                 });
@@ -390,9 +404,31 @@ impl Hir {
                 scope.add_symbol(self, symbol, false);
                 Some(symbol)
             }
-            Expr::Paren(expr) => expr
-                .expr()
-                .and_then(|expr| self.add_expression(source, scope, false, expr)),
+            Expr::Paren(expr) => match expr.expr() {
+                Some(inner) => self.add_expression(source, scope, false, inner),
+                // `()`
+                None => {
+                    let symbol = self.add_symbol(SymbolData {
+                        export: false,
+                        parent_scope: Scope::default(),
+                        source: SourceInfo {
+                            source: Some(source),
+                            text_range: expr.syntax().text_range().into(),
+                            selection_text_range: None,
+                        },
+                        kind: SymbolKind::Lit(LitSymbol {
+                            value: Value::Unit,
+                            template_parts: Vec::new(),
+                            interpolated_scopes: Vec::new(),
+                            targets: Vec::new(),
+                        }),
+                        ty: self.builtin_types.void,
+                    });
+
+                    scope.add_symbol(self, symbol, false);
+                    Some(symbol)
+                }
+            },
             Expr::Array(expr) => {
                 let symbol_data = SymbolData {
                     export: false,
@@ -441,6 +477,58 @@ impl Hir {
                 Some(symbol)
             }
             Expr::Object(expr) => {
+                let mut fields: IndexMap<String, ObjectField> = IndexMap::default();
+                let mut duplicate_fields = Vec::new();
+
+                for field in expr.fields() {
+                    if let (Some(name), Some(expr)) = (field.property(), field.expr()) {
+                        let (property_name, property_range) = field_name(&name);
+                        let value = self.add_expression(source, scope, false, expr);
+
+                        let field_symbol = self.add_symbol(SymbolData {
+                            export: false,
+                            parent_scope: Scope::default(),
+                            source: SourceInfo {
+                                source: Some(source),
+                                text_range: field.syntax().text_range().into(),
+                                selection_text_range: property_range.into(),
+                            },
+                            kind: SymbolKind::Field(FieldSymbol {
+                                name: property_name.clone(),
+                                docs: String::new(),
+                                value,
+                                references: HashSet::default(),
+                            }),
+                            ty: self.builtin_types.unknown,
+                        });
+                        scope.add_symbol(self, field_symbol, false);
+
+                        let object_field = ObjectField {
+                            property_name,
+                            property_syntax: SourceInfo {
+                                source: Some(source),
+                                text_range: name.text_range().into(),
+                                selection_text_range: None,
+                            },
+                            field_syntax: SourceInfo {
+                                source: Some(source),
+                                text_range: field.syntax().text_range().into(),
+                                selection_text_range: None,
+                            },
+                            value,
+                            symbol: field_symbol,
+                        };
+
+                        // Rhai rejects repeated keys, only the first
+                        // one is part of the object and the rest are reported.
+                        if fields.contains_key(&object_field.property_name) {
+                            duplicate_fields.push(object_field);
+                        } else {
+                            fields.insert(object_field.property_name.clone(), object_field);
+                        }
+                    }
+                }
+
                 let symbol_data = SymbolData {
                     export: false,
                     parent_scope: Scope::default(),
@@ -450,29 +538,8 @@ impl Hir {
                         selection_text_range: None,
                     },
                     kind: SymbolKind::Object(ObjectSymbol {
-                        fields: expr
-                            .fields()
-                            .filter_map(|field| match (field.property(), field.expr()) {
-                                (Some(name), Some(expr)) => Some((
-                                    name.text().to_string(),
-                                    ObjectField {
-                                        property_name: name.text().to_string(),
-                                        property_syntax: SourceInfo {
-                                            source: Some(source),
-                                            text_range: name.text_range().into(),
-                                            selection_text_range: None,
-                                        },
-                                        field_syntax: SourceInfo {
-                                            source: Some(source),
-                                            text_range: field.syntax().text_range().into(),
-                                            selection_text_range: None,
-                                        },
-                                        value: self.add_expression(source, scope, false, expr),
-                                    },
-                                )),
-                                _ => None,
-                            })
-                            .collect(),
+                        fields,
+                        duplicate_fields,
                     }),
                     ty: self.builtin_types.unknown,
                 };
@@ -561,6 +628,7 @@ impl Hir {
                     kind: SymbolKind::Closure(ClosureSymbol {
                         scope: closure_scope,
                         expr: closure_expr_symbol,
+                        captures: IndexSet::default(),
                     }),
                     ty: self.builtin_types.unknown,
                 });
@@ -683,6 +751,8 @@ impl Hir {
                     ..ScopeData::default()
                 });
 
+                let mut pat_symbols = Vec::new();
+
                 if let Some(pat) = expr.pat() {
                     for ident in pat.idents() {
                         let ident_symbol = self.add_symbol(SymbolData {
@@ -702,6 +772,7 @@ impl Hir {
                             ty: self.builtin_types.unknown,
                         });
                         scope.add_symbol(self, ident_symbol, false);
+                        pat_symbols.push(ident_symbol);
                     }
                 }
 
@@ -722,6 +793,7 @@ impl Hir {
                             .iterable()
                             .and_then(|expr| self.add_expression(source, scope, false, expr)),
                         scope: for_scope,
+                        pat_symbols,
                     }),
                     ty: self.builtin_types.unknown,
                 };
@@ -817,7 +889,7 @@ impl Hir {
                         arm_list
                             .arms()
                             .map(|arm| {
-                                let condition = None;
+                                let mut condition = None;
                                 let mut left = None;
                                 let mut right = None;
 
@@ -840,7 +912,7 @@ impl Hir {
                                 }
 
                                 if let Some(expr) = arm.condition().and_then(|c| c.expr()) {
-                                    left = self.add_expression(source, scope, false, expr);
+                                    condition = self.add_expression(source, scope, false, expr);
                                 }
 
                                 if let Some(expr) = arm.pattern_expr() {
@@ -1039,10 +1111,15 @@ impl Hir {
             Expr::Export(expr) => {
                 let target = expr.export_target().and_then(|target| match target {
                     ExportTarget::ExprLet(expr) => {
-                        self.add_expression(source, scope, can_export, Expr::Let(expr))
+                        let symbol = self.add_expression(source, scope, false, Expr::Let(expr))?;
+                        self.symbol_mut(symbol).export = can_export;
+                        Some(symbol)
                     }
                     ExportTarget::ExprConst(expr) => {
-                        self.add_expression(source, scope, can_export, Expr::Const(expr))
+                        let symbol =
+                            self.add_expression(source, scope, false, Expr::Const(expr))?;
+                        self.symbol_mut(symbol).export = can_export;
+                        Some(symbol)
                     }
                     ExportTarget::Ident(expr) => {
                         let symbol = self.add_symbol(SymbolData {
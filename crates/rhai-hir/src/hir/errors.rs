@@ -1,9 +1,16 @@
 use crate::{
     error::{Error, ErrorKind},
-    source::Source,
-    symbol::SymbolKind,
-    HashMap, Hir, Symbol,
+    eval::Value,
+    scope::ScopeParent,
+    source::{Source, SourceKind},
+    symbol::{
+        BinaryOpKind, BinarySymbol, CallSymbol, FnSymbol, ReferenceTarget, SymbolData, SymbolKind,
+    },
+    ty::{Type, TypeKind},
+    HashMap, HashSet, Hir, Module, Symbol,
 };
+use itertools::Either;
+use rhai_rowan::{syntax::SyntaxKind, TextRange};
 
 impl Hir {
     #[must_use]
@@ -14,6 +21,8 @@ impl Hir {
             self.collect_errors_from_symbol(symbol, &mut errors);
         }
 
+        self.collect_cyclic_import_errors(None, &mut errors);
+
         errors
     }
 
@@ -21,31 +30,328 @@ impl Hir {
     pub fn errors_for_source(&self, source: Source) -> Vec<Error> {
         let mut errors = Vec::new();
 
-        for (symbol, _) in self
-            .symbols()
-            .filter(|(_, symbol_data)| symbol_data.source.source == Some(source))
-        {
+        for (symbol, _) in self.symbols_in_sources(Some(&HashSet::from_iter([source]))) {
             self.collect_errors_from_symbol(symbol, &mut errors);
         }
 
+        self.collect_cyclic_import_errors(Some(source), &mut errors);
+
         errors
     }
 
+    fn collect_cyclic_import_errors(&self, source: Option<Source>, errors: &mut Vec<Error>) {
+        let imports = match source {
+            Some(source) => Either::Left(self.source_indices.get(source).into_iter()),
+            None => Either::Right(self.source_indices.values()),
+        }
+        .flat_map(|index| index.imports.iter().copied());
+
+        for import in imports {
+            let Some(mut cycle) = self.import_cycle(import) else {
+                continue;
+            };
+
+            // Every import of a cycle finds it, so it is only
+            // reported for the import with the lowest key.
+            if cycle.iter().min() != Some(&import) {
+                continue;
+            }
+
+            cycle.remove(0);
+
+            errors.push(Error {
+                kind: ErrorKind::CyclicImport { import, cycle },
+            });
+        }
+    }
+
+    /// The module the given path segment is looked up in,
+    /// that is the target of the previous segment.
+    fn path_segment_module(&self, segment: Symbol) -> Option<Module> {
+        let Some(ScopeParent::Symbol(path)) = self[self[segment].parent_scope].parent else {
+            return None;
+        };
+
+        let segments = &self[path].kind.as_path()?.segments;
+        let idx = segments.iter().position(|&s| s == segment)?;
+
+        self.target_module(segments[idx.checked_sub(1)?])
+    }
+
+    /// Strings that name functions, e.g. in `Fn("name")`,
+    /// must name a visible function.
+    fn collect_fn_name_errors(&self, symbol: Symbol, errors: &mut Vec<Error>) {
+        let Some(argument) = self.fn_name_argument(symbol) else {
+            return;
+        };
+
+        let Some(lit) = self[argument]
+            .kind
+            .as_lit()
+            .filter(|l| l.targets.is_empty())
+        else {
+            return;
+        };
+
+        let name = lit.value.as_string().map_or("", String::as_str);
+
+        errors.push(Error {
+            kind: ErrorKind::UnresolvedReference {
+                reference_symbol: argument,
+                similar_name: self.find_similar_name(argument, name),
+            },
+        });
+    }
+
+    /// Calls of closures and function pointers must pass
+    /// as many arguments as the function has parameters.
+    fn collect_fn_pointer_arity_errors(
+        &self,
+        symbol: Symbol,
+        call: &CallSymbol,
+        errors: &mut Vec<Error>,
+    ) {
+        let (pointer, is_curry) = if let Some((pointer, method)) = self.fn_pointer_method(symbol) {
+            (pointer, method == "curry")
+        } else {
+            // Functions are overloaded by arity, only variables are checked.
+            let Some(lhs) = call.lhs.filter(|&lhs| {
+                matches!(
+                    self[lhs].kind.as_reference().and_then(|r| r.target),
+                    Some(ReferenceTarget::Symbol(target)) if self[target].kind.is_decl()
+                )
+            }) else {
+                return;
+            };
+
+            (lhs, false)
+        };
+
+        let Some(f) = self[self[pointer].ty].kind.as_fn() else {
+            return;
+        };
+
+        let (expected, found) = (f.params.len(), call.arguments.len());
+
+        // Any amount of the parameters can be curried.
+        if found == expected || (is_curry && found < expected) {
+            return;
+        }
+
+        errors.push(Error {
+            kind: ErrorKind::FnPointerArityMismatch {
+                call: symbol,
+                expected,
+                found,
+            },
+        });
+    }
+
+    /// Operators that are declared in definitions must have an overload
+    /// that accepts the types of the operands.
+    fn collect_operator_overload_errors(
+        &self,
+        symbol: Symbol,
+        binary: &BinarySymbol,
+        errors: &mut Vec<Error>,
+    ) {
+        if binary.operator.is_some() || binary.is_field_access() {
+            return;
+        }
+
+        let (Some(lhs), Some(rhs)) = (binary.lhs, binary.rhs) else {
+            return;
+        };
+
+        let name = &binary.lookup_text;
+        let (lhs_ty, rhs_ty) = (self[lhs].ty, self[rhs].ty);
+
+        // Only report operands with types that are fully known,
+        // overloads are not matched against the members of unions.
+        if [lhs_ty, rhs_ty].into_iter().any(|ty| {
+            matches!(
+                self[ty].kind,
+                TypeKind::Unknown | TypeKind::Union(_) | TypeKind::Never
+            )
+        }) {
+            return;
+        }
+
+        if self.is_builtin_operation(name, lhs_ty, rhs_ty) {
+            return;
+        }
+
+        // Other built-in operators are not declared either, so we only report
+        // operands that some overload of the operator is declared for.
+        if !self.operators().any(|op| {
+            op.name == *name
+                && (op.lhs_ty.is(self, lhs_ty, true)
+                    || op.rhs_ty.is_some_and(|rhs| rhs.is(self, rhs_ty, true)))
+        }) {
+            return;
+        }
+
+        errors.push(Error {
+            kind: ErrorKind::NoMatchingOperator {
+                operation: symbol,
+                name: name.clone(),
+                lhs_ty: lhs_ty.fmt(self).to_string(),
+                rhs_ty: rhs_ty.fmt(self).to_string(),
+            },
+        });
+    }
+
+    /// Whether Rhai implements the operator for the operand types,
+    /// these operators are never declared in definitions.
+    fn is_builtin_operation(&self, name: &str, lhs_ty: Type, rhs_ty: Type) -> bool {
+        use TypeKind::{Array, Bool, Char, Float, Int, String};
+
+        let operands = (&self[lhs_ty].kind, &self[rhs_ty].kind);
+        let numbers = matches!(operands, (Int | Float, Int | Float));
+        let texts = matches!(operands, (Char | String, Char | String));
+
+        match name {
+            "+" => numbers || texts || matches!(operands, (Array(_), Array(_))),
+            "-" | "*" | "/" | "%" | "**" => numbers,
+            "&" | "|" | "^" => matches!(operands, (Int, Int) | (Bool, Bool)),
+            "<<" | ">>" => matches!(operands, (Int, Int)),
+            "&&" | "||" => matches!(operands, (Bool, Bool)),
+            // Values of different types are never equal.
+            "==" | "!=" => true,
+            "<" | "<=" | ">" | ">=" => numbers || texts || matches!(operands, (Bool, Bool)),
+            _ => false,
+        }
+    }
+
+    fn collect_deprecated_operator_errors(
+        &self,
+        symbol: Symbol,
+        binary: &BinarySymbol,
+        errors: &mut Vec<Error>,
+    ) {
+        if let Some(reason) = self.binary_operator_deprecation(binary) {
+            errors.push(Error {
+                kind: ErrorKind::Deprecated {
+                    reference_symbol: symbol,
+                    target: None,
+                    name: binary.lookup_text.clone(),
+                    reason,
+                },
+            });
+        }
+    }
+
+    /// Whether the field reference reads a field that is
+    /// definitely not part of the object map.
+    ///
+    /// Empty object maps are usually filled dynamically,
+    /// so any field can exist in them.
+    fn is_absent_field(&self, reference: Symbol, name: &str) -> bool {
+        let Some(access) = self.parent_expression(reference) else {
+            return false;
+        };
+
+        let Some(lhs) = self[access]
+            .kind
+            .as_binary()
+            .filter(|b| b.is_field_access() && b.rhs == Some(reference))
+            .and_then(|b| b.lhs)
+        else {
+            return false;
+        };
+
+        let Some(object) = self.object_of(self[lhs].ty) else {
+            return false;
+        };
+
+        if object.fields.is_empty() || object.fields.contains_key(name) {
+            return false;
+        }
+
+        // Fields of variables that are modified in untracked ways can exist.
+        if let Some(decl) = self[lhs]
+            .kind
+            .as_reference()
+            .and_then(|r| r.target)
+            .and_then(|target| target.as_symbol().copied())
+            .and_then(|target| self.symbol(target))
+            .and_then(|target| target.kind.as_decl())
+        {
+            if decl
+                .references
+                .iter()
+                .any(|&r| self.extends_fields_dynamically(r))
+            {
+                return false;
+            }
+        }
+
+        // Assignments add the field, calls are method calls.
+        !self
+            .parent_expression(access)
+            .is_some_and(|parent| match &self[parent].kind {
+                SymbolKind::Binary(b) => b.lookup_text == "=" && b.lhs == Some(access),
+                SymbolKind::Call(call) => call.lhs == Some(access),
+                _ => false,
+            })
+    }
+
     fn collect_errors_from_symbol(&self, symbol: Symbol, errors: &mut Vec<Error>) {
         if let Some(symbol_data) = self.symbol(symbol) {
             match &symbol_data.kind {
                 SymbolKind::Ref(r) => {
-                    if !r.field_access && r.target.is_none() && r.name != "this" {
+                    if !r.field_access
+                        && r.target.is_none()
+                        && r.name != "this"
+                        && !self.is_guarded_by_is_def_var(symbol, &r.name)
+                    {
+                        if let Some(item) = r
+                            .part_of_path
+                            .then(|| self.path_segment_module(symbol))
+                            .flatten()
+                            .and_then(|m| self.find_private_in_module(m, &r.name))
+                        {
+                            errors.push(Error {
+                                kind: ErrorKind::PrivateModuleItem {
+                                    reference_symbol: symbol,
+                                    item,
+                                    name: r.name.clone(),
+                                },
+                            });
+                            return;
+                        }
+
                         errors.push(Error {
                             kind: ErrorKind::UnresolvedReference {
                                 reference_symbol: symbol,
                                 similar_name: self.find_similar_name(symbol, &r.name),
                             },
                         });
+                    } else if r.field_access {
+                        if self.is_absent_field(symbol, &r.name) {
+                            errors.push(Error {
+                                kind: ErrorKind::AbsentField {
+                                    reference_symbol: symbol,
+                                    name: r.name.clone(),
+                                },
+                            });
+                        }
+                    } else if let Some(&target) = r.target.as_ref().and_then(|t| t.as_symbol()) {
+                        if let Some(reason) = self.symbol(target).and_then(SymbolData::deprecated) {
+                            errors.push(Error {
+                                kind: ErrorKind::Deprecated {
+                                    reference_symbol: symbol,
+                                    target: Some(target),
+                                    name: r.name.clone(),
+                                    reason,
+                                },
+                            });
+                        }
                     }
                 }
                 SymbolKind::Fn(f) => {
                     if f.is_def {
+                        self.collect_fn_declaration_errors(symbol, f, errors);
                         return;
                     }
 
@@ -54,7 +360,10 @@ impl Hir {
                         .iter()
                         .any(|(_, m)| m.scope == symbol_data.parent_scope);
 
-                    if !top_level {
+                    if top_level {
+                        self.collect_duplicate_fn_errors(symbol, f, errors);
+                        self.collect_undeclared_fn_errors(symbol, f, errors);
+                    } else {
                         errors.push(Error {
                             kind: ErrorKind::NestedFunction { function: symbol },
                         });
@@ -83,6 +392,10 @@ impl Hir {
                         }
                     }
                 }
+                SymbolKind::Call(call) => {
+                    self.collect_fn_name_errors(symbol, errors);
+                    self.collect_fn_pointer_arity_errors(symbol, call, errors);
+                }
                 SymbolKind::Import(import) => {
                     if import.target.is_none() {
                         errors.push(Error {
@@ -90,8 +403,352 @@ impl Hir {
                         });
                     }
                 }
+                SymbolKind::Binary(binary) => {
+                    self.collect_operator_overload_errors(symbol, binary, errors);
+                    self.collect_deprecated_operator_errors(symbol, binary, errors);
+
+                    if !matches!(
+                        binary.op,
+                        Some(BinaryOpKind::Regular(
+                            SyntaxKind::OP_DIV | SyntaxKind::OP_MOD
+                        ))
+                    ) {
+                        return;
+                    }
+
+                    // Float division by zero results in `inf` or `NaN`,
+                    // so the dividend has to be known to be an integer.
+                    if !binary
+                        .lhs
+                        .is_some_and(|lhs| self[self[lhs].ty].kind.is_int())
+                    {
+                        return;
+                    }
+
+                    if let Some(Value::Int(0)) = binary.rhs.map(|rhs| self.eval_symbol(rhs)) {
+                        errors.push(Error {
+                            kind: ErrorKind::DivisionByZero { operation: symbol },
+                        });
+                    }
+                }
+                SymbolKind::If(if_symbol) => {
+                    for &(condition, _) in &if_symbol.branches {
+                        let Some(condition) = condition else {
+                            continue;
+                        };
+
+                        // Literal `true` and `false` are most likely intentional.
+                        if self[condition].kind.is_lit() {
+                            continue;
+                        }
+
+                        if let Value::Bool(value) = self.eval_symbol(condition) {
+                            errors.push(Error {
+                                kind: ErrorKind::ConstantCondition { condition, value },
+                            });
+                        }
+                    }
+                }
+                SymbolKind::Switch(switch) => {
+                    let mut default_symbol = None;
+                    let mut patterns: Vec<(Value, Symbol)> = Vec::new();
+
+                    for arm in &switch.arms {
+                        let Some(pat) = arm.pat_expr else {
+                            continue;
+                        };
+
+                        if let Some(default_symbol) = default_symbol {
+                            errors.push(Error {
+                                kind: ErrorKind::UnreachableSwitchArm {
+                                    arm_symbol: pat,
+                                    default_symbol,
+                                },
+                            });
+                            continue;
+                        }
+
+                        if arm.condition_expr.is_some() {
+                            continue;
+                        }
+
+                        if self[pat].kind.is_discard() {
+                            default_symbol = Some(pat);
+                            continue;
+                        }
+
+                        let value = self.eval_symbol(pat);
+
+                        if !value.is_known() {
+                            continue;
+                        }
+
+                        if let Some(&(_, existing_symbol)) =
+                            patterns.iter().find(|(v, _)| *v == value)
+                        {
+                            errors.push(Error {
+                                kind: ErrorKind::DuplicateSwitchArm {
+                                    duplicate_symbol: pat,
+                                    existing_symbol,
+                                },
+                            });
+                        } else {
+                            patterns.push((value, pat));
+                        }
+                    }
+                }
+                SymbolKind::Object(object) => {
+                    for field in &object.duplicate_fields {
+                        let existing_range = object
+                            .fields
+                            .get(&field.property_name)
+                            .and_then(|f| f.property_syntax.text_range);
+
+                        if let (Some(duplicate_range), Some(existing_range)) =
+                            (field.property_syntax.text_range, existing_range)
+                        {
+                            errors.push(Error {
+                                kind: ErrorKind::DuplicateObjectKey {
+                                    object: symbol,
+                                    name: field.property_name.clone(),
+                                    duplicate_range,
+                                    existing_range,
+                                },
+                            });
+                        }
+                    }
+                }
+                SymbolKind::Closure(closure) => {
+                    if closure.captures.is_empty() {
+                        return;
+                    }
+
+                    let mut scope = Some(symbol_data.parent_scope);
+
+                    while let Some(s) = scope {
+                        let Some(scope_data) = self.scope(s) else {
+                            break;
+                        };
+
+                        if let Some(for_loop) = scope_data
+                            .parent
+                            .and_then(|p| p.as_symbol().copied())
+                            .and_then(|sym| self.symbol(sym))
+                            .and_then(|data| data.kind.as_for())
+                        {
+                            for &var in &for_loop.pat_symbols {
+                                if !closure.captures.contains(&var) {
+                                    continue;
+                                }
+
+                                let reference = self
+                                    .symbols()
+                                    .filter(|(_, data)| {
+                                        matches!(
+                                            data.kind.as_reference().and_then(|r| r.target),
+                                            Some(ReferenceTarget::Symbol(t)) if t == var
+                                        ) && self.is_scope_within(data.parent_scope, closure.scope)
+                                    })
+                                    .min_by_key(|(_, data)| {
+                                        data.source.text_range.map(TextRange::start)
+                                    })
+                                    .map(|(sym, _)| sym);
+
+                                if let Some(reference_symbol) = reference {
+                                    errors.push(Error {
+                                        kind: ErrorKind::ClosureCapturesLoopVariable {
+                                            reference_symbol,
+                                            loop_variable: var,
+                                            name: self[var].name(self).unwrap_or_default().into(),
+                                        },
+                                    });
+                                }
+                            }
+                        }
+
+                        scope = self.parent_scope_of(s);
+                    }
+                }
                 _ => {}
             }
         }
     }
+
+    /// Check that functions declared in a definition file that
+    /// describes a script match the functions in the script.
+    fn collect_fn_declaration_errors(&self, symbol: Symbol, f: &FnSymbol, errors: &mut Vec<Error>) {
+        let symbol_data = &self[symbol];
+
+        let Some(module) = symbol_data
+            .source
+            .source
+            .and_then(|source| self.sources.get(source))
+            .and_then(|source| self.modules.get(source.module))
+        else {
+            return;
+        };
+
+        let describes_script = module.scope == symbol_data.parent_scope
+            && module
+                .sources
+                .iter()
+                .any(|&source| matches!(self[source].kind, SourceKind::Script));
+
+        if !describes_script {
+            return;
+        }
+
+        let arity = f.arity(self);
+
+        // Script functions with the same name and their arities.
+        let arities = self
+            .scope_symbols(symbol_data.parent_scope)
+            .filter_map(|other| {
+                let other_fn = self[other].kind.as_fn().filter(|other_fn| {
+                    !other_fn.is_def
+                        && other_fn.name == f.name
+                        && other_fn.getter == f.getter
+                        && other_fn.setter == f.setter
+                })?;
+
+                Some((other, other_fn.arity(self)))
+            })
+            .collect::<Vec<_>>();
+
+        if arities
+            .iter()
+            .any(|&(_, function_arity)| function_arity == arity)
+        {
+            return;
+        }
+
+        if let Some(&(function, found)) = arities
+            .iter()
+            .min_by_key(|(function, _)| self[*function].text_range().map(TextRange::start))
+        {
+            errors.push(Error {
+                kind: ErrorKind::FnArityMismatch {
+                    definition: symbol,
+                    function,
+                    name: f.name.clone(),
+                    expected: arity,
+                    found,
+                },
+            });
+        } else {
+            errors.push(Error {
+                kind: ErrorKind::MissingFnDefinition {
+                    definition: symbol,
+                    name: f.name.clone(),
+                    arity,
+                },
+            });
+        }
+    }
+
+    /// Check that exported script functions are declared in the
+    /// definition files that describe the script, if there are any.
+    fn collect_undeclared_fn_errors(&self, symbol: Symbol, f: &FnSymbol, errors: &mut Vec<Error>) {
+        let symbol_data = &self[symbol];
+
+        if !symbol_data.export || f.definition.is_some() {
+            return;
+        }
+
+        let Some(module) = symbol_data
+            .source
+            .source
+            .and_then(|source| self.sources.get(source))
+            .and_then(|source| self.modules.get(source.module))
+        else {
+            return;
+        };
+
+        let has_definition = module
+            .sources
+            .iter()
+            .any(|&source| matches!(self[source].kind, SourceKind::Def));
+
+        if !has_definition {
+            return;
+        }
+
+        // Declarations with the same name but a different arity
+        // are reported as arity mismatches instead.
+        let declared = self.scope_symbols(symbol_data.parent_scope).any(|other| {
+            self[other]
+                .kind
+                .as_fn()
+                .is_some_and(|other_fn| other_fn.is_def && other_fn.name == f.name)
+        });
+
+        if declared {
+            return;
+        }
+
+        errors.push(Error {
+            kind: ErrorKind::UndeclaredFn {
+                function: symbol,
+                name: f.name.clone(),
+                arity: f.arity(self),
+            },
+        });
+    }
+
+    /// Find other top-level functions with the same name and arity.
+    ///
+    /// Errors are reported at the later script definition,
+    /// declarations in definition files describe the script functions,
+    /// but script functions cannot be overloaded by types.
+    fn collect_duplicate_fn_errors(&self, symbol: Symbol, f: &FnSymbol, errors: &mut Vec<Error>) {
+        let symbol_data = &self[symbol];
+        let arity = f.arity(self);
+
+        let same_signature = self
+            .scope_symbols(symbol_data.parent_scope)
+            .filter(|&other| other != symbol)
+            .filter(|&other| {
+                self[other].kind.as_fn().is_some_and(|other_fn| {
+                    other_fn.name == f.name
+                        && other_fn.getter == f.getter
+                        && other_fn.setter == f.setter
+                        && other_fn.arity(self) == arity
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(&definition) = self.fn_declarations(symbol).get(1) {
+            errors.push(Error {
+                kind: ErrorKind::ConflictingFnDefinition {
+                    function: symbol,
+                    definition,
+                    name: f.name.clone(),
+                    arity,
+                },
+            });
+        }
+
+        let start = symbol_data.text_range().map(TextRange::start);
+
+        let existing = same_signature
+            .into_iter()
+            .filter(|&other| {
+                let other_data = &self[other];
+                other_data.source.source == symbol_data.source.source
+                    && other_data.kind.as_fn().is_some_and(|f| !f.is_def)
+                    && other_data.text_range().map(TextRange::start) < start
+            })
+            .min_by_key(|&other| self[other].text_range().map(TextRange::start));
+
+        if let Some(existing_symbol) = existing {
+            errors.push(Error {
+                kind: ErrorKind::DuplicateFnDefinition {
+                    duplicate_symbol: symbol,
+                    existing_symbol,
+                    name: f.name.clone(),
+                    arity,
+                },
+            });
+        }
+    }
 }
@@ -0,0 +1,126 @@
+use crate::{
+    eval::Value,
+    symbol::{BinaryOpKind, ReferenceTarget, SymbolKind},
+    Hir, IndexMap, Symbol,
+};
+use rhai_rowan::syntax::SyntaxKind;
+
+/// Guard against deeply nested or cyclic constant definitions.
+const MAX_EVAL_DEPTH: usize = 64;
+
+impl Hir {
+    /// Evaluate an expression symbol if its value
+    /// is known without running the script.
+    ///
+    /// Only literals, `const` declarations and operations
+    /// on them are evaluated, [`Value::Unknown`] is returned
+    /// for everything else.
+    #[must_use]
+    pub fn eval_symbol(&self, symbol: Symbol) -> Value {
+        self.eval_symbol_inner(symbol, 0)
+    }
+
+    fn eval_symbol_inner(&self, symbol: Symbol, depth: usize) -> Value {
+        if depth > MAX_EVAL_DEPTH {
+            return Value::Unknown;
+        }
+
+        let Some(symbol_data) = self.symbol(symbol) else {
+            return Value::Unknown;
+        };
+
+        let eval = |sym: Option<Symbol>| {
+            sym.map_or(Value::Unknown, |sym| self.eval_symbol_inner(sym, depth + 1))
+        };
+
+        match &symbol_data.kind {
+            SymbolKind::Lit(lit) => {
+                if lit.interpolated_scopes.is_empty() {
+                    return lit.value.clone();
+                }
+
+                let mut s = String::new();
+
+                for (i, part) in lit.template_parts.iter().enumerate() {
+                    s += part;
+
+                    if let Some(&scope) = lit.interpolated_scopes.get(i) {
+                        let scope_data = &self[scope];
+
+                        if scope_data.symbols.len() != 1 {
+                            return Value::Unknown;
+                        }
+
+                        match eval(scope_data.symbols.first().copied()).to_text() {
+                            Some(text) => s += &text,
+                            None => return Value::Unknown,
+                        }
+                    }
+                }
+
+                Value::String(s)
+            }
+            SymbolKind::Decl(decl) => {
+                if decl.is_const {
+                    eval(decl.value)
+                } else {
+                    Value::Unknown
+                }
+            }
+            SymbolKind::Ref(r) => match r.target {
+                Some(ReferenceTarget::Symbol(target)) => eval(Some(target)),
+                _ => Value::Unknown,
+            },
+            SymbolKind::Path(p) => eval(p.segments.last().copied()),
+            SymbolKind::Unary(unary) => match unary.op {
+                Some(op) => Value::unary(op, &eval(unary.rhs)),
+                None => Value::Unknown,
+            },
+            SymbolKind::Binary(binary) => match &binary.op {
+                Some(BinaryOpKind::Regular(SyntaxKind::PUNCT_DOT)) => {
+                    let Value::Map(mut fields) = eval(binary.lhs) else {
+                        return Value::Unknown;
+                    };
+
+                    binary
+                        .rhs
+                        .and_then(|rhs| self[rhs].kind.as_reference())
+                        .and_then(|field| fields.shift_remove(&field.name))
+                        .unwrap_or(Value::Unknown)
+                }
+                Some(BinaryOpKind::Regular(op)) => {
+                    Value::binary(&eval(binary.lhs), *op, &eval(binary.rhs))
+                }
+                _ => Value::Unknown,
+            },
+            SymbolKind::Array(arr) => {
+                Value::Array(arr.values.iter().map(|&v| eval(Some(v))).collect())
+            }
+            SymbolKind::Object(obj) => Value::Map(
+                obj.fields
+                    .iter()
+                    .map(|(name, field)| (name.clone(), eval(field.value)))
+                    .collect::<IndexMap<_, _>>(),
+            ),
+            SymbolKind::Index(idx) => match (eval(idx.base), eval(idx.index)) {
+                (Value::Array(mut values), Value::Int(i)) => {
+                    // Negative indices count from the end.
+                    let i = if i < 0 {
+                        i64::try_from(values.len()).ok().map(|len| len + i)
+                    } else {
+                        Some(i)
+                    };
+
+                    i.and_then(|i| usize::try_from(i).ok())
+                        .filter(|&i| i < values.len())
+                        .map_or(Value::Unknown, |i| values.swap_remove(i))
+                }
+                (Value::Map(mut fields), Value::String(key)) => {
+                    fields.shift_remove(&key).unwrap_or(Value::Unknown)
+                }
+                _ => Value::Unknown,
+            },
+            _ => Value::Unknown,
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use super::*;
+use rhai_rowan::{TextRange, TextSize};
+
+/// Lookup tables for a single source.
+///
+/// Built once a source is added, and dropped with it.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SourceIndex {
+    /// Every symbol of the source, including the ones without a text range.
+    pub(crate) all_symbols: Vec<Symbol>,
+    /// Import statements in the order they appear in.
+    pub(crate) imports: Vec<Symbol>,
+    pub(crate) symbols: RangeIndex<Symbol>,
+    pub(crate) symbol_selections: RangeIndex<Symbol>,
+    pub(crate) scopes: RangeIndex<Scope>,
+}
+
+/// A static interval tree over text ranges.
+///
+/// The entries are sorted by their start offsets and form an implicit
+/// binary search tree where the middle of every slice is the root
+/// of the slice. Each node also stores the maximum end offset of its subtree,
+/// so that subtrees that cannot contain an offset can be skipped.
+#[derive(Debug, Clone)]
+pub(crate) struct RangeIndex<T> {
+    entries: Vec<(TextRange, T)>,
+    max_ends: Vec<TextSize>,
+}
+
+impl<T> Default for RangeIndex<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            max_ends: Vec::new(),
+        }
+    }
+}
+
+impl<T: Copy> RangeIndex<T> {
+    pub(crate) fn new(mut entries: Vec<(TextRange, T)>) -> Self {
+        // The sort is stable, so entries with the same start
+        // keep their original order.
+        entries.sort_by_key(|(range, _)| range.start());
+
+        let mut max_ends = vec![TextSize::default(); entries.len()];
+        Self::build(&entries, &mut max_ends, 0, entries.len());
+
+        Self { entries, max_ends }
+    }
+
+    fn build(
+        entries: &[(TextRange, T)],
+        max_ends: &mut [TextSize],
+        lo: usize,
+        hi: usize,
+    ) -> TextSize {
+        if lo >= hi {
+            return TextSize::default();
+        }
+
+        let mid = lo + (hi - lo) / 2;
+
+        let max_end = entries[mid]
+            .0
+            .end()
+            .max(Self::build(entries, max_ends, lo, mid))
+            .max(Self::build(entries, max_ends, mid + 1, hi));
+
+        max_ends[mid] = max_end;
+        max_end
+    }
+
+    /// All the entries that contain the given offset ordered
+    /// by their start offsets.
+    pub(crate) fn containing(&self, offset: TextSize, inclusive: bool) -> Vec<(TextRange, T)> {
+        let mut found = Vec::new();
+        self.collect_containing(offset, inclusive, 0, self.entries.len(), &mut found);
+        found
+    }
+
+    fn collect_containing(
+        &self,
+        offset: TextSize,
+        inclusive: bool,
+        lo: usize,
+        hi: usize,
+        found: &mut Vec<(TextRange, T)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+
+        if self.max_ends[mid] < offset {
+            return;
+        }
+
+        self.collect_containing(offset, inclusive, lo, mid, found);
+
+        let (range, value) = self.entries[mid];
+
+        if range.start() > offset {
+            return;
+        }
+
+        if (inclusive && range.contains_inclusive(offset)) || range.contains(offset) {
+            found.push((range, value));
+        }
+
+        self.collect_containing(offset, inclusive, mid + 1, hi, found);
+    }
+}
+
+impl Hir {
+    /// (Re)build the positional index of the given source.
+    pub(crate) fn index_source(&mut self, source: Source) {
+        let mut all_symbols = Vec::new();
+        let mut imports = Vec::new();
+        let mut symbols = Vec::new();
+        let mut symbol_selections = Vec::new();
+
+        for (symbol, data) in self.symbols.iter().filter(|(_, d)| d.source.is(source)) {
+            all_symbols.push(symbol);
+
+            if data.kind.is_import() {
+                imports.push(symbol);
+            }
+
+            if let Some(range) = data.source.text_range {
+                symbols.push((range, symbol));
+            }
+
+            if let Some(range) = data.source.selection_text_range {
+                symbol_selections.push((range, symbol));
+            }
+        }
+
+        let scopes = self
+            .scopes
+            .iter()
+            .filter(|(_, d)| d.source.is(source))
+            .filter_map(|(scope, d)| d.source.text_range.map(|range| (range, scope)))
+            .collect();
+
+        self.source_indices.insert(
+            source,
+            SourceIndex {
+                all_symbols,
+                imports,
+                symbols: RangeIndex::new(symbols),
+                symbol_selections: RangeIndex::new(symbol_selections),
+                scopes: RangeIndex::new(scopes),
+            },
+        );
+    }
+}
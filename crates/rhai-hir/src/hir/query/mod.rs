@@ -1,3 +1,4 @@
+use crate::scope::ScopeParent;
 use rhai_rowan::{parser, util::is_valid_ident, TextRange, TextSize};
 use std::cmp::Ordering;
 
@@ -26,56 +27,301 @@ impl Hir {
         offset: TextSize,
         inclusive: bool,
     ) -> Option<Symbol> {
-        self.symbols()
-            .filter(|(_, d)| d.source.is(source))
-            .filter_map(|(sym, d)| {
-                d.source.selection_text_range.and_then(|range| {
-                    if (inclusive && range.contains_inclusive(offset)) || range.contains(offset) {
-                        Some((sym, range))
-                    } else {
-                        None
-                    }
-                })
-            })
-            .min_by(|(_, r1), (_, r2)| range_scope(*r1, *r2))
-            .map(|(s, _)| s)
+        self.source_indices
+            .get(source)?
+            .symbol_selections
+            .containing(offset, inclusive)
+            .into_iter()
+            .filter(|(_, sym)| self.symbols.contains_key(*sym))
+            .min_by(|(r1, _), (r2, _)| range_scope(*r1, *r2))
+            .map(|(_, s)| s)
     }
 
     #[must_use]
     pub fn symbol_at(&self, source: Source, offset: TextSize, inclusive: bool) -> Option<Symbol> {
-        self.symbols()
-            .filter(|(_, d)| d.source.is(source))
-            .filter_map(|(sym, d)| {
-                d.source.text_range.and_then(|range| {
-                    if (inclusive && range.contains_inclusive(offset)) || range.contains(offset) {
-                        Some((sym, range))
-                    } else {
-                        None
-                    }
-                })
-            })
-            .min_by(|(_, r1), (_, r2)| range_scope(*r1, *r2))
-            .map(|(s, _)| s)
+        self.source_indices
+            .get(source)?
+            .symbols
+            .containing(offset, inclusive)
+            .into_iter()
+            .filter(|(_, sym)| self.symbols.contains_key(*sym))
+            .min_by(|(r1, _), (r2, _)| range_scope(*r1, *r2))
+            .map(|(_, s)| s)
     }
 
     #[must_use]
     pub fn scope_at(&self, source: Source, offset: TextSize, inclusive: bool) -> Option<Scope> {
-        self.scopes()
-            .filter(|(_, d)| d.source.is(source))
-            .filter_map(|(sym, d)| {
-                d.source.text_range.and_then(|range| {
-                    if (inclusive && range.contains_inclusive(offset)) || range.contains(offset) {
-                        Some((sym, range))
-                    } else {
-                        None
-                    }
-                })
+        self.source_indices
+            .get(source)
+            .and_then(|index| {
+                index
+                    .scopes
+                    .containing(offset, inclusive)
+                    .into_iter()
+                    .filter(|(_, scope)| self.scopes.contains_key(*scope))
+                    .min_by(|(r1, _), (r2, _)| range_scope(*r1, *r2))
+                    .map(|(_, s)| s)
             })
-            .min_by(|(_, r1), (_, r2)| range_scope(*r1, *r2))
-            .map(|(s, _)| s)
             .or_else(|| self.module_by_source(source).map(|m| self[m].scope))
     }
 
+    /// Return the scope that contains the given scope, if any.
+    ///
+    /// If the scope belongs to a symbol (e.g. a function body),
+    /// the scope of that symbol is returned.
+    #[must_use]
+    pub fn parent_scope_of(&self, scope: Scope) -> Option<Scope> {
+        match self[scope].parent? {
+            ScopeParent::Scope(s) => Some(s),
+            ScopeParent::Symbol(sym) => self.symbol(sym).map(|data| data.parent_scope),
+        }
+    }
+
+    /// Whether `scope` is `ancestor` or is nested anywhere inside it.
+    #[must_use]
+    pub fn is_scope_within(&self, scope: Scope, ancestor: Scope) -> bool {
+        let mut current = Some(scope);
+
+        while let Some(s) = current {
+            if s == ancestor {
+                return true;
+            }
+
+            if !self.scopes.contains_key(s) {
+                return false;
+            }
+
+            current = self.parent_scope_of(s);
+        }
+
+        false
+    }
+
+    /// The binary expression, call, index expression or declaration
+    /// that has the given symbol as a direct operand, callee, argument or value.
+    #[must_use]
+    pub fn parent_expression(&self, symbol: Symbol) -> Option<Symbol> {
+        let sym_data = self.symbol(symbol)?;
+        let range = sym_data.source.text_range?;
+
+        let mut containing = self
+            .source_indices
+            .get(sym_data.source.source?)?
+            .symbols
+            .containing(range.start(), true)
+            .into_iter()
+            .filter(|(r, sym)| {
+                *sym != symbol && r.contains_range(range) && self.symbols.contains_key(*sym)
+            })
+            .collect::<Vec<_>>();
+        containing.sort_by_key(|(r, _)| r.len());
+
+        containing
+            .into_iter()
+            .map(|(_, sym)| sym)
+            .find(|&sym| match &self[sym].kind {
+                SymbolKind::Binary(b) => b.lhs == Some(symbol) || b.rhs == Some(symbol),
+                SymbolKind::Call(call) => {
+                    call.lhs == Some(symbol) || call.arguments.contains(&symbol)
+                }
+                SymbolKind::Index(index) => {
+                    index.base == Some(symbol) || index.index == Some(symbol)
+                }
+                SymbolKind::Decl(decl) => decl.value == Some(symbol),
+                _ => false,
+            })
+    }
+
+    /// The field name and assigned value if the symbol is the object
+    /// of a property assignment such as `obj.field = value`
+    /// or `obj["field"] = value`.
+    #[must_use]
+    pub fn assigned_field(&self, symbol: Symbol) -> Option<(&str, Option<Symbol>)> {
+        let access = self.parent_expression(symbol)?;
+
+        let name = match &self[access].kind {
+            SymbolKind::Binary(b) if b.is_field_access() && b.lhs == Some(symbol) => b
+                .rhs
+                .and_then(|rhs| self[rhs].kind.as_reference())?
+                .name
+                .as_str(),
+            SymbolKind::Index(index) if index.base == Some(symbol) => index
+                .index
+                .and_then(|index| self[index].kind.as_lit())
+                .filter(|lit| lit.interpolated_scopes.is_empty())
+                .and_then(|lit| lit.value.as_string())?
+                .as_str(),
+            _ => return None,
+        };
+
+        let assignment = self.parent_expression(access)?;
+        let assignment_binary = self[assignment].kind.as_binary()?;
+
+        if assignment_binary.lookup_text != "=" || assignment_binary.lhs != Some(access) {
+            return None;
+        }
+
+        Some((name, assignment_binary.rhs))
+    }
+
+    /// Whether fields might be added to the object map of the given
+    /// reference in ways that are not tracked, e.g. with `obj[key] = value`,
+    /// `obj += other`, `obj.mixin(other)` or script methods that assign
+    /// to `this`, or if the reference is reassigned, aliased
+    /// or passed to a function.
+    #[must_use]
+    pub fn extends_fields_dynamically(&self, reference: Symbol) -> bool {
+        let Some(parent) = self.parent_expression(reference) else {
+            return false;
+        };
+
+        match &self[parent].kind {
+            SymbolKind::Binary(b) if b.is_field_access() => {
+                let extends = b.rhs.is_some_and(|rhs| {
+                    self[rhs].kind.as_reference().is_some_and(|r| {
+                        r.name == "mixin"
+                            || r.name == "fill_with"
+                            || self.visible_symbols_from_symbol(rhs).any(|s| {
+                                self[s]
+                                    .kind
+                                    .as_fn()
+                                    .is_some_and(|f| !f.is_def && f.name == r.name)
+                            })
+                    })
+                });
+
+                extends
+                    && b.lhs == Some(reference)
+                    && self.parent_expression(parent).is_some_and(|call| {
+                        self[call]
+                            .kind
+                            .as_call()
+                            .is_some_and(|call| call.lhs == Some(parent))
+                    })
+            }
+            SymbolKind::Binary(b) => {
+                b.is_assignment()
+                    && (b.lhs == Some(reference)
+                        || (b.lookup_text == "=" && b.rhs == Some(reference)))
+            }
+            SymbolKind::Index(index) => {
+                index.base == Some(reference)
+                    && self.assigned_field(reference).is_none()
+                    && self.parent_expression(parent).is_some_and(|assignment| {
+                        self[assignment]
+                            .kind
+                            .as_binary()
+                            .is_some_and(|b| b.is_assignment() && b.lhs == Some(parent))
+                    })
+            }
+            SymbolKind::Call(call) => call.arguments.contains(&reference),
+            SymbolKind::Decl(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Declarations in definition files with the same signature
+    /// as the given script function.
+    ///
+    /// Only definition files that describe the script itself
+    /// declare functions in the same module.
+    #[must_use]
+    pub fn fn_declarations(&self, symbol: Symbol) -> Vec<Symbol> {
+        let sym_data = &self[symbol];
+
+        let Some(f) = sym_data.kind.as_fn().filter(|f| !f.is_def) else {
+            return Vec::new();
+        };
+
+        let arity = f.arity(self);
+
+        self.scope_symbols(sym_data.parent_scope)
+            .filter(|&other| {
+                self[other].kind.as_fn().is_some_and(|other_fn| {
+                    other_fn.is_def
+                        && other_fn.name == f.name
+                        && other_fn.getter == f.getter
+                        && other_fn.setter == f.setter
+                        && other_fn.arity(self) == arity
+                })
+            })
+            .collect()
+    }
+
+    /// The string literal argument of a call that names a function,
+    /// either in `Fn("name")` or in a call of a host function
+    /// that takes function names.
+    #[must_use]
+    pub fn fn_name_argument(&self, call: Symbol) -> Option<Symbol> {
+        let call = self[call].kind.as_call()?;
+
+        let callee = self[call.lhs?].kind.as_reference()?;
+
+        if callee.part_of_path {
+            return None;
+        }
+
+        let is_fn_name = if callee.name == "Fn" {
+            call.arguments.len() == 1
+        } else {
+            self.fn_name_functions.contains(&callee.name)
+        };
+
+        if !is_fn_name {
+            return None;
+        }
+
+        let argument = call.arguments.first().copied()?;
+        let lit = self[argument].kind.as_lit()?;
+
+        if !lit.interpolated_scopes.is_empty() || lit.value.as_string().is_none() {
+            return None;
+        }
+
+        Some(argument)
+    }
+
+    /// The function that a `Fn("name")` call points to.
+    ///
+    /// Functions are overloaded by arity, if there are several
+    /// functions with the name it is not known which one is called.
+    #[must_use]
+    pub fn fn_pointer_target(&self, call: Symbol) -> Option<Symbol> {
+        let callee = self[call].kind.as_call()?.lhs?;
+
+        if self[callee].name(self) != Some("Fn") {
+            return None;
+        }
+
+        match self[self.fn_name_argument(call)?]
+            .kind
+            .as_lit()?
+            .targets
+            .as_slice()
+        {
+            &[target] => Some(target),
+            _ => None,
+        }
+    }
+
+    /// The function pointer and the method name if the call is
+    /// a `call` or `curry` method call, e.g. `f.call(1)`.
+    #[must_use]
+    pub fn fn_pointer_method(&self, call: Symbol) -> Option<(Symbol, &str)> {
+        let access = self[self[call].kind.as_call()?.lhs?].kind.as_binary()?;
+
+        if !access.is_field_access() {
+            return None;
+        }
+
+        let method = self[access.rhs?].kind.as_reference()?;
+
+        match method.name.as_str() {
+            "call" | "curry" => Some((access.lhs?, method.name.as_str())),
+            _ => None,
+        }
+    }
+
     #[must_use]
     pub fn source_by_url(&self, url: &Url) -> Option<Source> {
         for (src, data) in self.sources.iter() {
@@ -111,6 +357,56 @@ impl Hir {
     pub fn operator_by_name(&self, name: &str) -> Option<&OpSymbol> {
         self.operators().find(|&op| op.name == name)
     }
+
+    /// The deprecation reason of the operator of a binary expression.
+    ///
+    /// Operators are deprecated if the overload used for the operands is,
+    /// otherwise only if they have overloads and all of them are.
+    #[must_use]
+    pub fn binary_operator_deprecation(&self, binary: &BinarySymbol) -> Option<String> {
+        if let Some(overload) = binary.operator.and_then(|op| self.symbol(op)) {
+            return overload.kind.as_op().and_then(OpSymbol::deprecated);
+        }
+
+        self.operators()
+            .filter(|op| op.name == binary.lookup_text)
+            .map(OpSymbol::deprecated)
+            .collect::<Option<Vec<_>>>()
+            .and_then(|reasons| reasons.into_iter().next())
+    }
+
+    /// Find the overload of the binary operator with the given name
+    /// that accepts the operand types.
+    ///
+    /// Overloads with exactly matching operand types are preferred,
+    /// otherwise the last compatible one is chosen.
+    #[must_use]
+    pub fn binary_operator_overload(
+        &self,
+        name: &str,
+        lhs_ty: Type,
+        rhs_ty: Type,
+    ) -> Option<Symbol> {
+        let overloads = self
+            .symbols
+            .iter()
+            .filter_map(|(symbol, data)| Some((symbol, data.kind.as_op()?)))
+            .filter(|(_, op)| {
+                op.name == name
+                    && op.lhs_ty.is(self, lhs_ty, false)
+                    && op.rhs_ty.is_some_and(|rhs| rhs.is(self, rhs_ty, false))
+            })
+            .collect::<Vec<_>>();
+
+        overloads
+            .iter()
+            .find(|(_, op)| {
+                op.lhs_ty.is(self, lhs_ty, true)
+                    && op.rhs_ty.is_some_and(|rhs| rhs.is(self, rhs_ty, true))
+            })
+            .or_else(|| overloads.last())
+            .map(|&(symbol, _)| symbol)
+    }
 }
 
 /// Used for filtering shadowed symbols.
@@ -1,4 +1,9 @@
-use crate::scope::ScopeParent;
+use crate::{
+    module::{ModuleGraph, ModuleImport},
+    scope::ScopeParent,
+    HashMap, IndexSet,
+};
+use std::collections::{hash_map::Entry, VecDeque};
 
 use super::*;
 
@@ -65,8 +70,8 @@ impl Hir {
     pub fn missing_modules(&self) -> impl ExactSizeIterator<Item = Url> {
         let mut missing = Vec::new();
 
-        for (symbol, data) in &self.symbols {
-            if let SymbolKind::Import(import) = &data.kind {
+        for symbol in self.import_symbols() {
+            if let SymbolKind::Import(import) = &self[symbol].kind {
                 if let Some(import_path) = import.import_path(self) {
                     let m = self.module_by_symbol(symbol).unwrap();
 
@@ -88,6 +93,205 @@ impl Hir {
         missing.into_iter()
     }
 
+    /// The URLs each of the missing modules can be loaded from,
+    /// in order of priority.
+    ///
+    /// Unlike [`Hir::missing_modules`], this includes all locations
+    /// the module resolver would search, e.g. additional search paths.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn missing_module_candidates(&self) -> Vec<Vec<Url>> {
+        let mut missing = Vec::new();
+
+        for symbol in self.import_symbols() {
+            let SymbolKind::Import(import) = &self[symbol].kind else {
+                continue;
+            };
+
+            let Some(import_path) = import.import_path(self) else {
+                continue;
+            };
+
+            let m = self.module_by_symbol(symbol).unwrap();
+
+            let Ok(module_url) = self
+                .module_resolver
+                .resolve_url_from_module(self, m, import_path)
+            else {
+                continue;
+            };
+
+            if self.module_by_url(&module_url).is_some() {
+                continue;
+            }
+
+            if let Ok(candidates) =
+                self.module_resolver
+                    .resolve_candidate_urls_from_module(self, m, import_path)
+            {
+                if !missing.contains(&candidates) {
+                    missing.push(candidates);
+                }
+            }
+        }
+
+        missing
+    }
+
+    /// Import relations between all the known modules.
+    ///
+    /// Imports that could not be resolved are not included.
+    #[must_use]
+    pub fn module_graph(&self) -> ModuleGraph {
+        let mut graph = ModuleGraph::default();
+
+        for (source, index) in &self.source_indices {
+            let from = self[source].module;
+
+            if from.is_null() {
+                continue;
+            }
+
+            for &symbol in &index.imports {
+                if let Some(to) = self[symbol].kind.as_import().and_then(|i| i.target) {
+                    graph.edges.push(ModuleImport {
+                        from,
+                        to,
+                        import: symbol,
+                    });
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// All modules that the given module has resolved references to,
+    /// either directly or via other modules.
+    ///
+    /// This is the counterpart of [`Hir::dependent_modules`].
+    #[must_use]
+    pub fn dependency_modules(&self, module: Module) -> IndexSet<Module> {
+        let mut dependencies = IndexSet::default();
+        let mut stack = vec![module];
+
+        while let Some(m) = stack.pop() {
+            let sources = self
+                .modules
+                .get(m)
+                .into_iter()
+                .flat_map(|m| m.sources.iter());
+
+            for &source in sources {
+                for &dependency in self.source_dependencies.get(source).into_iter().flatten() {
+                    if dependencies.insert(dependency) {
+                        stack.push(dependency);
+                    }
+                }
+            }
+        }
+
+        dependencies
+    }
+
+    /// Import statements of the given source.
+    #[must_use]
+    pub fn imports_in_source(&self, source: Source) -> &[Symbol] {
+        self.source_indices
+            .get(source)
+            .map_or(&[], |index| &index.imports)
+    }
+
+    fn import_symbols(&self) -> impl Iterator<Item = Symbol> + '_ {
+        self.source_indices
+            .values()
+            .flat_map(|index| index.imports.iter().copied())
+    }
+
+    /// All modules with resolved references to the given module,
+    /// either directly or via other modules.
+    ///
+    /// Unlike [`ModuleGraph::transitive_dependents`], this does not
+    /// look at every import in the HIR, and modules that refer to the module
+    /// without importing it, e.g. via definitions, are included.
+    #[must_use]
+    pub fn dependent_modules(&self, module: Module) -> IndexSet<Module> {
+        let mut dependents = IndexSet::default();
+        let mut stack = vec![module];
+
+        while let Some(m) = stack.pop() {
+            for &source in self.module_dependents.get(&m).into_iter().flatten() {
+                let Some(dependent) = self.sources.get(source).map(|s| s.module) else {
+                    continue;
+                };
+
+                if !dependent.is_null() && dependents.insert(dependent) {
+                    stack.push(dependent);
+                }
+            }
+        }
+
+        dependents
+    }
+
+    /// The shortest chain of imports that starts with the given import
+    /// and leads back to the module of the import.
+    ///
+    /// Only the modules reachable from the import are visited.
+    #[must_use]
+    pub fn import_cycle(&self, import: Symbol) -> Option<Vec<Symbol>> {
+        let from = self.module_of_symbol(import)?;
+        let target = self.symbol(import)?.kind.as_import()?.target?;
+
+        // The import that first reached each module.
+        let mut reached_by: HashMap<Module, Symbol> = HashMap::default();
+        reached_by.insert(target, import);
+
+        let mut queue = VecDeque::from([target]);
+
+        while let Some(m) = queue.pop_front() {
+            if m == from {
+                let mut cycle = Vec::new();
+                let mut current = m;
+
+                loop {
+                    let edge = reached_by[&current];
+                    cycle.push(edge);
+
+                    if edge == import {
+                        break;
+                    }
+
+                    current = self.module_of_symbol(edge)?;
+                }
+
+                cycle.reverse();
+                return Some(cycle);
+            }
+
+            for (edge, to) in self.imports_of_module(m) {
+                if let Entry::Vacant(entry) = reached_by.entry(to) {
+                    entry.insert(edge);
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Resolved imports in the sources of the module
+    /// along with their targets.
+    fn imports_of_module(&self, module: Module) -> impl Iterator<Item = (Symbol, Module)> + '_ {
+        self.modules
+            .get(module)
+            .into_iter()
+            .flat_map(|m| m.sources.iter())
+            .filter_map(|&source| self.source_indices.get(source))
+            .flat_map(|index| index.imports.iter())
+            .filter_map(|&import| Some((import, self[import].kind.as_import()?.target?)))
+    }
+
     /// Resolve a symbol in a module.
     #[must_use]
     pub fn find_in_module(&self, module: Module, name: &str) -> Option<Symbol> {
@@ -96,6 +300,19 @@ impl Hir {
             .find(|s| self[*s].name(self) == Some(name))
     }
 
+    /// Find a function or variable in a module that exists
+    /// but is not visible from outside of it.
+    ///
+    /// Only functions that are not `private` and
+    /// variables that are explicitly exported can be accessed from other modules.
+    #[must_use]
+    pub fn find_private_in_module(&self, module: Module, name: &str) -> Option<Symbol> {
+        self.scope_symbols(self[module].scope)
+            .filter(|s| !self[*s].export)
+            .filter(|s| matches!(self[*s].kind, SymbolKind::Fn(_) | SymbolKind::Decl(_)))
+            .find(|s| self[*s].name(self) == Some(name))
+    }
+
     /// Recursively resolve a module from a reference.
     #[must_use]
     pub fn target_module(&self, reference_symbol: Symbol) -> Option<Module> {
@@ -250,7 +250,7 @@ fn collect_symbol_scope_iters<'h>(
             }
         }
         SymbolKind::Object(sym) => {
-            for (_, field) in &sym.fields {
+            for field in sym.fields.values().chain(&sym.duplicate_fields) {
                 if let Some(sym) = field.value {
                     collect_symbol_scope_iters(hir, iters, sym);
                 }
@@ -355,6 +355,7 @@ fn collect_symbol_scope_iters<'h>(
         }
         SymbolKind::Op(_)
         | SymbolKind::Ref(_)
+        | SymbolKind::Field(_)
         | SymbolKind::Continue(_)
         | SymbolKind::Discard(_)
         | SymbolKind::Virtual(VirtualSymbol::Proxy(..))
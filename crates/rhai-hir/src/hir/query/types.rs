@@ -1,4 +1,8 @@
-use crate::{hir::BuiltinTypes, Hir};
+use crate::{
+    hir::BuiltinTypes,
+    ty::{Object, Type},
+    Hir, TypeKind,
+};
 
 impl Hir {
     #[must_use]
@@ -6,4 +10,14 @@ impl Hir {
     pub const fn builtin_types(&self) -> BuiltinTypes {
         self.builtin_types
     }
+
+    /// The object map shape of the type, type aliases are followed.
+    #[must_use]
+    pub fn object_of(&self, ty: Type) -> Option<&Object> {
+        match &self.types.get(ty)?.kind {
+            TypeKind::Object(object) => Some(object),
+            TypeKind::Alias(_, ty) => self.object_of(*ty),
+            _ => None,
+        }
+    }
 }
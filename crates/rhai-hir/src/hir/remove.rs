@@ -8,7 +8,16 @@ use crate::{
 
 impl Hir {
     pub fn remove_source(&mut self, source: Source) {
-        self.sources.remove(source);
+        let module = self.sources.remove(source).map(|s| s.module);
+        self.source_indices.remove(source);
+
+        // References from and to the source are cleared
+        // the next time the sources are resolved.
+        self.pending_sources.insert(source);
+
+        if let Some(dependents) = module.and_then(|m| self.module_dependents.get(&m)) {
+            self.pending_sources.extend(dependents.iter().copied());
+        }
 
         let symbols_to_remove = self
             .symbols
@@ -37,6 +46,10 @@ impl Hir {
         }
 
         self.cleanup_modules();
+
+        if let Some(m) = module.filter(|&m| !self.modules.contains_key(m)) {
+            self.module_dependents.remove(&m);
+        }
     }
 
     /// Remove scopes and symbols of modules that
@@ -183,7 +196,9 @@ impl Hir {
                 let symbols = object
                     .fields
                     .into_iter()
-                    .filter_map(|(_, field)| field.value)
+                    .map(|(_, field)| field)
+                    .chain(object.duplicate_fields)
+                    .filter_map(|field| field.value)
                     .collect::<Vec<_>>();
 
                 for symbol in symbols {
@@ -272,6 +287,7 @@ impl Hir {
             }
             SymbolKind::Continue(_)
             | SymbolKind::Discard(_)
+            | SymbolKind::Field(_)
             | SymbolKind::Op(_)
             | SymbolKind::TypeDecl(_) => {}
             SymbolKind::Export(e) => {
@@ -1,31 +1,301 @@
 use crate::{
-    symbol::{ReferenceTarget, SymbolKind, VirtualSymbol},
-    Hir, Module, Symbol,
+    scope::ScopeParent,
+    source::Source,
+    symbol::{ReferenceTarget, SymbolData, SymbolKind, VirtualSymbol},
+    HashSet, Hir, IndexSet, Module, Symbol,
 };
-use itertools::Itertools;
+use itertools::{Either, Itertools};
+use slotmap::Key;
 
+mod narrowing;
 mod types;
 
 impl Hir {
     pub fn clear_references(&mut self) {
-        let ref_symbols = self.symbols.iter_mut();
-
-        for (_, sym_data) in ref_symbols {
-            match &mut sym_data.kind {
-                SymbolKind::Fn(f) => f.references.clear(),
-                SymbolKind::Decl(d) => {
-                    d.target = None;
-                    d.references.clear();
-                }
-                SymbolKind::Ref(r) => r.target = None,
+        self.clear_references_in(None);
+    }
+
+    /// Clear resolved references of symbols in the given sources,
+    /// or in all sources if `None`.
+    ///
+    /// References from the given sources are also removed from
+    /// symbols in the modules they depend on.
+    fn clear_references_in(&mut self, sources: Option<&HashSet<Source>>) {
+        let Some(sources) = sources else {
+            self.source_dependencies.clear();
+            self.module_dependents.clear();
+
+            for sym_data in self.symbols.values_mut() {
+                clear_symbol_references(sym_data);
+            }
+
+            return;
+        };
+
+        // The given sources can include removed sources
+        // that left references behind.
+        let resolved_sources = sources
+            .iter()
+            .copied()
+            .filter(|&source| self.source_dependencies.contains_key(source))
+            .collect::<Vec<_>>();
+
+        let dependency_sources = resolved_sources
+            .iter()
+            .flat_map(|&source| self.source_dependencies[source].iter().copied())
+            .chain([self.static_module])
+            .filter_map(|m| self.modules.get(m))
+            .flat_map(|m| m.sources.iter().copied())
+            .filter(|source| !sources.contains(source))
+            .collect::<HashSet<_>>();
+
+        for source in resolved_sources {
+            self.remove_source_dependencies(source);
+        }
+
+        // References from the given sources that
+        // are stored in symbols of other sources.
+        let stale_references = self
+            .symbols_in_sources(Some(&dependency_sources))
+            .flat_map(|(_, sym_data)| match &sym_data.kind {
+                SymbolKind::Fn(f) => f.references.iter().copied().collect(),
+                SymbolKind::Decl(d) => d.references.iter().copied().collect(),
+                SymbolKind::Field(f) => f.references.iter().copied().collect(),
+                _ => Vec::new(),
+            })
+            .filter(|&r| {
+                self.symbols.get(r).is_none_or(|r_data| {
+                    r_data
+                        .source
+                        .source
+                        .is_some_and(|source| sources.contains(&source))
+                })
+            })
+            .collect::<HashSet<_>>();
+
+        for symbol in self.symbol_keys_in_sources(Some(&dependency_sources)) {
+            match &mut self.symbol_mut(symbol).kind {
+                SymbolKind::Fn(f) => f.references.retain(|r| !stale_references.contains(r)),
+                SymbolKind::Decl(d) => d.references.retain(|r| !stale_references.contains(r)),
+                SymbolKind::Field(f) => f.references.retain(|r| !stale_references.contains(r)),
                 _ => {}
             }
         }
+
+        for symbol in self.symbol_keys_in_sources(Some(sources)) {
+            clear_symbol_references(self.symbol_mut(symbol));
+        }
+    }
+
+    /// Record the modules that the resolved symbols of the given sources
+    /// (or all sources if `None`) refer to, so that the sources are resolved
+    /// again when any of the modules change.
+    ///
+    /// The static module is not recorded, as changes to it
+    /// always cause everything to be resolved again.
+    pub(crate) fn collect_source_dependencies(&mut self, sources: Option<&HashSet<Source>>) {
+        let mut dependencies: Vec<(Source, Module)> = Vec::new();
+
+        for (_, sym_data) in self.symbols_in_sources(sources) {
+            let Some(source) = sym_data.source.source else {
+                continue;
+            };
+
+            // Import targets are included in `target`.
+            let linked_symbols = match &sym_data.kind {
+                SymbolKind::Fn(f) => f.definition.into_iter().collect(),
+                SymbolKind::Binary(b) => b.operator.into_iter().collect(),
+                SymbolKind::Lit(l) => l.targets.clone(),
+                _ => Vec::new(),
+            };
+
+            let mut modules = linked_symbols
+                .into_iter()
+                .filter_map(|target| self.module_of_symbol(target))
+                .collect::<Vec<_>>();
+
+            match sym_data.target() {
+                Some(ReferenceTarget::Module(m)) => modules.push(m),
+                Some(ReferenceTarget::Symbol(target)) => {
+                    modules.extend(self.module_of_symbol(target));
+
+                    // Module aliases and virtual modules also depend
+                    // on the modules they refer to.
+                    if let Some(ReferenceTarget::Module(m)) =
+                        self.symbol(target).and_then(SymbolData::target)
+                    {
+                        modules.push(m);
+                    }
+                }
+                None => {}
+            }
+
+            let own_module = self.sources.get(source).map(|s| s.module);
+
+            dependencies.extend(
+                modules
+                    .into_iter()
+                    .filter(|&m| m != self.static_module && Some(m) != own_module)
+                    .map(|m| (source, m)),
+            );
+        }
+
+        for (source, module) in dependencies {
+            match self.source_dependencies.get_mut(source) {
+                Some(modules) => {
+                    modules.insert(module);
+                }
+                None => {
+                    self.source_dependencies
+                        .insert(source, HashSet::from_iter([module]));
+                }
+            }
+
+            self.module_dependents
+                .entry(module)
+                .or_default()
+                .insert(source);
+        }
+    }
+
+    fn remove_source_dependencies(&mut self, source: Source) {
+        let Some(modules) = self.source_dependencies.remove(source) else {
+            return;
+        };
+
+        for module in modules {
+            if let Some(dependents) = self.module_dependents.get_mut(&module) {
+                dependents.remove(&source);
+
+                if dependents.is_empty() {
+                    self.module_dependents.remove(&module);
+                }
+            }
+        }
+    }
+
+    /// The module of the source of the given symbol.
+    pub(crate) fn module_of_symbol(&self, symbol: Symbol) -> Option<Module> {
+        let source = self.symbol(symbol)?.source.source?;
+        self.sources.get(source).map(|s| s.module)
+    }
+
+    /// Symbols of the given sources, or all symbols if `None`.
+    pub(crate) fn symbols_in_sources<'h>(
+        &'h self,
+        sources: Option<&'h HashSet<Source>>,
+    ) -> impl Iterator<Item = (Symbol, &'h SymbolData)> + 'h {
+        match sources {
+            None => Either::Left(self.symbols.iter()),
+            Some(sources) => Either::Right(
+                sources
+                    .iter()
+                    .filter_map(|&source| self.source_indices.get(source))
+                    .flat_map(|index| index.all_symbols.iter())
+                    .filter_map(|&symbol| Some((symbol, self.symbols.get(symbol)?))),
+            ),
+        }
+    }
+
+    /// Same as [`Hir::symbols_in_sources`], but the symbols are collected so that
+    /// the HIR can be modified while iterating over them.
+    fn symbol_keys_in_sources(&self, sources: Option<&HashSet<Source>>) -> Vec<Symbol> {
+        self.symbols_in_sources(sources)
+            .map(|(symbol, _)| symbol)
+            .collect()
     }
 
     pub fn resolve_all(&mut self) {
+        self.pending_sources.clear();
         self.resolve_references();
         self.resolve_types();
+        self.collect_source_dependencies(None);
+    }
+
+    /// Resolve references and types after the given sources
+    /// were added or changed.
+    ///
+    /// Only the modules of the sources, the modules that had
+    /// unresolved imports of them and all modules that depend on these
+    /// directly or transitively are resolved again,
+    /// everything else is left untouched.
+    /// Sources that were removed since the last resolution
+    /// and their dependents are included as well.
+    ///
+    /// Changes to the static module affect every module,
+    /// in that case this is equivalent to [`Hir::resolve_all`].
+    pub fn resolve_sources(&mut self, sources: impl IntoIterator<Item = Source>) {
+        let mut changed_sources = core::mem::take(&mut self.pending_sources);
+        changed_sources.extend(sources);
+
+        let mut affected_modules: IndexSet<Module> = IndexSet::default();
+
+        for &source in &changed_sources {
+            match self.sources.get(source).map(|s| s.module) {
+                Some(m) if !m.is_null() && m != self.static_module => {
+                    affected_modules.insert(m);
+                }
+                Some(_) => return self.resolve_all(),
+                None => {}
+            }
+        }
+
+        // The modules might not have existed when other modules tried
+        // to import them, these imports are resolved again below.
+        let urls = affected_modules
+            .iter()
+            .filter_map(|&m| self[m].url().cloned())
+            .collect::<Vec<_>>();
+
+        for url in urls {
+            let importers = self
+                .unresolved_imports
+                .remove(&url)
+                .into_iter()
+                .flatten()
+                .filter_map(|source| self.sources.get(source))
+                .map(|s| s.module)
+                .filter(|m| !m.is_null())
+                .collect::<Vec<_>>();
+
+            affected_modules.extend(importers);
+        }
+
+        let mut idx = 0;
+        while let Some(&m) = affected_modules.get_index(idx) {
+            let dependents = self
+                .module_dependents
+                .get(&m)
+                .into_iter()
+                .flatten()
+                .filter_map(|&source| self.sources.get(source))
+                .map(|s| s.module)
+                .filter(|m| !m.is_null())
+                .collect::<Vec<_>>();
+
+            affected_modules.extend(dependents);
+            idx += 1;
+        }
+
+        let affected_sources = affected_modules
+            .into_iter()
+            .filter_map(|m| self.modules.get(m))
+            .flat_map(|m| m.sources.iter().copied())
+            .chain(changed_sources)
+            .collect::<HashSet<_>>();
+
+        self.clear_references_in(Some(&affected_sources));
+        self.resolve_imports(Some(&affected_sources));
+        self.resolve_paths(Some(&affected_sources));
+        self.resolve_scope_references(Some(&affected_sources));
+        self.resolve_fn_name_references(Some(&affected_sources));
+        self.resolve_closure_captures(Some(&affected_sources));
+        self.resolve_fn_definitions(Some(&affected_sources));
+
+        self.resolve_type_aliases(Some(&affected_sources));
+        self.resolve_types_for_symbols(Some(&affected_sources));
+
+        self.collect_source_dependencies(Some(&affected_sources));
     }
 
     pub fn resolve_references(&mut self) {
@@ -34,20 +304,22 @@ impl Hir {
         // The ordering is important here,
         // e.g. paths already rely on submodules
         // to be resolved.
-        self.resolve_imports();
-        self.resolve_paths();
-        self.resolve_scope_references();
+        self.resolve_imports(None);
+        self.resolve_paths(None);
+        self.resolve_scope_references(None);
+        self.resolve_fn_name_references(None);
+        self.resolve_closure_captures(None);
+        self.resolve_fn_definitions(None);
     }
 
     pub fn resolve_types(&mut self) {
-        self.resolve_type_aliases();
-        self.resolve_types_for_all_symbols();
+        self.resolve_type_aliases(None);
+        self.resolve_types_for_symbols(None);
     }
 
-    fn resolve_scope_references(&mut self) {
+    fn resolve_scope_references(&mut self, sources: Option<&HashSet<Source>>) {
         let ref_symbols_to_resolve: Vec<Symbol> = self
-            .symbols
-            .iter()
+            .symbols_in_sources(sources)
             .filter_map(|(s, data)| match &data.kind {
                 SymbolKind::Ref(ref_data) if !ref_data.part_of_path && !ref_data.field_access => {
                     Some(s)
@@ -101,17 +373,144 @@ impl Hir {
         }
     }
 
-    fn resolve_imports(&mut self) {
-        let import_symbols_to_resolve: Vec<Symbol> = self
-            .symbols
-            .iter()
-            .filter_map(|(s, data)| match &data.kind {
-                SymbolKind::Import(_) => Some(s),
-                _ => None,
+    /// Resolve string literals that name functions, e.g. in `Fn("name")`,
+    /// to all the visible functions with the same name.
+    fn resolve_fn_name_references(&mut self, sources: Option<&HashSet<Source>>) {
+        let references = self
+            .symbols_in_sources(sources)
+            .filter_map(|(symbol, _)| self.fn_name_argument(symbol))
+            .filter_map(|argument| {
+                let name = self[argument].kind.as_lit()?.value.as_string()?;
+
+                let targets = self
+                    .visible_symbols_from_symbol(argument)
+                    .filter(|&symbol| self[symbol].kind.as_fn().is_some_and(|f| f.name == *name))
+                    .collect::<Vec<_>>();
+
+                Some((argument, targets))
             })
-            .collect();
+            .collect::<Vec<_>>();
+
+        for (argument, targets) in references {
+            for &target in &targets {
+                if let Some(f) = self.symbol_mut(target).kind.as_fn_mut() {
+                    f.references.insert(argument);
+                }
+            }
+
+            if let Some(lit) = self.symbol_mut(argument).kind.as_lit_mut() {
+                lit.targets = targets;
+            }
+        }
+    }
+
+    fn resolve_closure_captures(&mut self, sources: Option<&HashSet<Source>>) {
+        let mut captures: Vec<(Symbol, Symbol)> = Vec::new();
+
+        for (_, ref_data) in self.symbols_in_sources(sources) {
+            let Some(ReferenceTarget::Symbol(target)) =
+                ref_data.kind.as_reference().and_then(|r| r.target)
+            else {
+                continue;
+            };
+
+            let target_data = &self[target];
+
+            if target_data.source.source != ref_data.source.source
+                || target_data.kind.as_decl().is_none_or(|d| d.is_import)
+            {
+                continue;
+            }
+
+            // Every closure between the reference and the declaration
+            // has to capture the variable.
+            let mut scope = Some(ref_data.parent_scope);
+
+            while let Some(s) = scope {
+                if s == target_data.parent_scope || !self.scopes.contains_key(s) {
+                    break;
+                }
+
+                if let Some(ScopeParent::Symbol(parent)) = self[s].parent {
+                    if self[parent].kind.is_closure() {
+                        captures.push((parent, target));
+                    }
+                }
+
+                scope = self.parent_scope_of(s);
+            }
+        }
+
+        for (closure, target) in captures {
+            if let Some(c) = self.symbol_mut(closure).kind.as_closure_mut() {
+                c.captures.insert(target);
+            }
+        }
+    }
+
+    /// Link script functions to their declarations in definition
+    /// files that describe the script.
+    ///
+    /// The declared types are used for the functions, and the docs
+    /// of the declarations are used if the functions have none.
+    fn resolve_fn_definitions(&mut self, sources: Option<&HashSet<Source>>) {
+        let definitions = self
+            .symbols_in_sources(sources)
+            .filter(|(_, data)| data.kind.as_fn().is_some_and(|f| !f.is_def))
+            .filter_map(|(symbol, _)| {
+                let definition = self.fn_declarations(symbol).first().copied()?;
+                Some((symbol, definition))
+            })
+            .collect::<Vec<_>>();
+
+        for (symbol, definition) in definitions {
+            let docs = self[definition]
+                .kind
+                .as_fn()
+                .map(|f| f.docs.clone())
+                .unwrap_or_default();
+
+            if let Some(f) = self.symbol_mut(symbol).kind.as_fn_mut() {
+                f.definition = Some(definition);
+
+                if f.docs.is_empty() && !docs.is_empty() {
+                    f.docs = docs;
+                    f.inherited_docs = true;
+                }
+            }
+        }
+    }
+
+    fn resolve_imports(&mut self, sources: Option<&HashSet<Source>>) {
+        // Entries of `unresolved_imports` are only dropped once the module exists,
+        // sources that stopped importing it are resolved once more at worst.
+        let import_symbols_to_resolve: Vec<(Source, Symbol)> = match sources {
+            Some(sources) => sources
+                .iter()
+                .filter_map(|&source| Some((source, self.source_indices.get(source)?)))
+                .flat_map(|(source, index)| index.imports.iter().map(move |&s| (source, s)))
+                .collect(),
+            None => {
+                self.unresolved_imports.clear();
+
+                self.source_indices
+                    .iter()
+                    .flat_map(|(source, index)| index.imports.iter().map(move |&s| (source, s)))
+                    .collect()
+            }
+        };
+
+        for (source, import_symbol) in import_symbols_to_resolve {
+            // The target module might have been removed since.
+            let import = self.symbol_mut(import_symbol).kind.as_import_mut().unwrap();
+            import.target = None;
+
+            if let Some(alias) = import.alias {
+                if let Some(alias_decl) = self.symbol_mut(alias).kind.as_decl_mut() {
+                    alias_decl.target = None;
+                }
+            }
 
-        for import_symbol in import_symbols_to_resolve {
             let module = match self.module_by_symbol(import_symbol) {
                 Some(m) => m,
                 None => {
@@ -136,7 +535,13 @@ impl Hir {
 
                     let target_module = match self.module_by_url(&import_url) {
                         Some(m) => m,
-                        None => continue,
+                        None => {
+                            self.unresolved_imports
+                                .entry(import_url)
+                                .or_default()
+                                .insert(source);
+                            continue;
+                        }
                     };
 
                     if let Some(alias) = import_symbol_data.alias {
@@ -155,10 +560,9 @@ impl Hir {
         }
     }
 
-    fn resolve_paths(&mut self) {
+    fn resolve_paths(&mut self, sources: Option<&HashSet<Source>>) {
         let path_symbols_to_resolve: Vec<Vec<Symbol>> = self
-            .symbols
-            .iter()
+            .symbols_in_sources(sources)
             .filter_map(|(_, data)| match &data.kind {
                 SymbolKind::Path(p) => Some(p.segments.clone()),
                 _ => None,
@@ -274,3 +678,30 @@ impl Hir {
         }
     }
 }
+
+fn clear_symbol_references(sym_data: &mut SymbolData) {
+    match &mut sym_data.kind {
+        SymbolKind::Fn(f) => {
+            f.references.clear();
+            f.definition = None;
+
+            if f.inherited_docs {
+                f.docs.clear();
+                f.inherited_docs = false;
+            }
+        }
+        SymbolKind::Decl(d) => {
+            // Targets of import aliases are resolved with the imports.
+            if !d.is_import {
+                d.target = None;
+            }
+            d.references.clear();
+        }
+        SymbolKind::Field(f) => f.references.clear(),
+        SymbolKind::Ref(r) => r.target = None,
+        SymbolKind::Binary(b) => b.operator = None,
+        SymbolKind::Lit(l) => l.targets.clear(),
+        SymbolKind::Closure(c) => c.captures.clear(),
+        _ => {}
+    }
+}
@@ -0,0 +1,504 @@
+//! Narrowing of variable types in branches guarded by type checks.
+//!
+//! The following idioms are recognized for a variable `x`:
+//!
+//! - `type_of(x) == "string"` and `type_of(x) != "string"`
+//! - `x == ()` and `x != ()`
+//! - `x == 42`, the variable has the type of the literal
+//! - `switch type_of(x) { "string" => ... }` and `switch x { () => ... }`
+//!
+//! Conditions can be combined with `&&`, `||` and `!`, previous
+//! conditions of `if` chains and switch arms are taken into account as well.
+//! Conditions no longer narrow the variable once it is assigned to.
+//!
+//! Unresolved references in branches guarded by `is_def_var("x")`
+//! are not errors.
+
+use crate::{
+    eval::Value,
+    source::SourceInfo,
+    symbol::{ReferenceTarget, SwitchArm, SymbolKind},
+    ty::{Type, TypeData},
+    Hir, IndexSet, Symbol, TypeKind,
+};
+
+/// A fact that holds wherever a symbol is evaluated.
+#[derive(Debug, Clone, Copy)]
+enum Guard {
+    /// The condition evaluated to the given value.
+    Condition(Symbol, bool),
+    /// The arm with the given index of a switch was selected.
+    SwitchArm(Symbol, usize),
+}
+
+/// How the variable appears in a condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Subject {
+    /// `x`
+    Value,
+    /// `type_of(x)`
+    TypeOf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TypeTest {
+    Int,
+    Float,
+    Bool,
+    Char,
+    String,
+    Timestamp,
+    Array,
+    Map,
+    Fn,
+    Unit,
+    /// Any other type returned by `type_of`, e.g. of custom types.
+    Named(String),
+}
+
+#[derive(Debug, Clone)]
+struct Narrowing {
+    test: TypeTest,
+    negated: bool,
+}
+
+impl TypeTest {
+    /// The test for a type name returned by `type_of`.
+    fn from_type_name(name: &str) -> Self {
+        match name {
+            "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64" | "u128"
+            | "int" => Self::Int,
+            "f32" | "f64" | "float" | "decimal" => Self::Float,
+            "bool" => Self::Bool,
+            "char" => Self::Char,
+            "string" => Self::String,
+            "timestamp" => Self::Timestamp,
+            "array" => Self::Array,
+            "map" => Self::Map,
+            "Fn" => Self::Fn,
+            "()" => Self::Unit,
+            name => Self::Named(name.to_string()),
+        }
+    }
+
+    /// The test for a literal the subject is compared to.
+    fn for_subject(subject: Subject, value: &Value) -> Option<Self> {
+        match subject {
+            Subject::Value => Self::of_value(value),
+            Subject::TypeOf => value.as_string().map(|name| Self::from_type_name(name)),
+        }
+    }
+
+    /// The test for the type of a literal.
+    fn of_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Int(_) => Some(Self::Int),
+            Value::Float(_) => Some(Self::Float),
+            Value::Bool(_) => Some(Self::Bool),
+            Value::String(_) => Some(Self::String),
+            Value::Char(_) => Some(Self::Char),
+            Value::Unit => Some(Self::Unit),
+            Value::Array(_) | Value::Map(_) | Value::Unknown => None,
+        }
+    }
+
+    fn matches(&self, hir: &Hir, ty: Type) -> bool {
+        let kind = match &hir[ty].kind {
+            TypeKind::Alias(name, _) if matches!(self, Self::Named(n) if n == name) => {
+                return true;
+            }
+            TypeKind::Alias(_, ty) => &hir[*ty].kind,
+            kind => kind,
+        };
+
+        match (self, kind) {
+            (Self::Int, TypeKind::Int)
+            | (Self::Float, TypeKind::Float)
+            | (Self::Bool, TypeKind::Bool)
+            | (Self::Char, TypeKind::Char)
+            | (Self::String, TypeKind::String)
+            | (Self::Timestamp, TypeKind::Timestamp)
+            | (Self::Array, TypeKind::Array(_))
+            | (Self::Map, TypeKind::Object(_))
+            | (Self::Fn, TypeKind::Fn(_))
+            | (Self::Unit, TypeKind::Void) => true,
+            (Self::Named(name), TypeKind::Primitive(ty) | TypeKind::Unresolved(ty)) => name == ty,
+            _ => false,
+        }
+    }
+
+    /// The type that passes the test, if there is only one.
+    fn ty(&self, hir: &Hir) -> Option<Type> {
+        let types = &hir.builtin_types;
+
+        match self {
+            Self::Int => Some(types.int),
+            Self::Float => Some(types.float),
+            Self::Bool => Some(types.bool),
+            Self::Char => Some(types.char),
+            Self::String => Some(types.string),
+            Self::Timestamp => Some(types.timestamp),
+            Self::Unit => Some(types.void),
+            Self::Array | Self::Map | Self::Fn | Self::Named(_) => None,
+        }
+    }
+}
+
+impl Hir {
+    /// The type of a variable at the given reference, narrowed
+    /// by the conditions that hold wherever the reference is evaluated.
+    pub(super) fn narrowed_type(&mut self, reference: Symbol, target: Symbol, ty: Type) -> Type {
+        if !self[target].kind.is_decl() {
+            return ty;
+        }
+
+        let narrowings = self
+            .guards_of(reference)
+            .into_iter()
+            .filter(|&guard| !self.is_assigned_after_guard(guard, target, reference))
+            .flat_map(|guard| self.guard_narrowings(guard, target))
+            .collect::<Vec<_>>();
+
+        let source = self[reference].source;
+        let mut ty = ty;
+
+        // The guards are collected from the innermost one.
+        for narrowing in narrowings.iter().rev() {
+            ty = self.narrow(ty, narrowing, source);
+        }
+
+        ty
+    }
+
+    /// Whether the variable is assigned to between the guard
+    /// and the reference, in which case the guard no longer holds.
+    fn is_assigned_after_guard(&self, guard: Guard, target: Symbol, reference: Symbol) -> bool {
+        let guard_symbol = match guard {
+            Guard::Condition(condition, _) => Some(condition),
+            Guard::SwitchArm(switch, _) => self[switch]
+                .kind
+                .as_switch()
+                .and_then(|switch| switch.target),
+        };
+
+        let (Some(guard_end), Some(reference_start)) = (
+            guard_symbol.and_then(|sym| self[sym].source.text_range),
+            self[reference].source.text_range,
+        ) else {
+            return false;
+        };
+
+        let (guard_end, reference_start) = (guard_end.end(), reference_start.start());
+        let source = self[reference].source.source;
+
+        let Some(decl) = self[target].kind.as_decl() else {
+            return false;
+        };
+
+        decl.references.iter().any(|&r| {
+            let Some(r_data) = self.symbol(r) else {
+                return false;
+            };
+
+            r_data.source.source == source
+                && r_data.source.text_range.is_some_and(|range| {
+                    guard_end <= range.start() && range.end() <= reference_start
+                })
+                && self.parent_expression(r).is_some_and(|parent| {
+                    self[parent]
+                        .kind
+                        .as_binary()
+                        .is_some_and(|b| b.is_assignment() && b.lhs == Some(r))
+                })
+        })
+    }
+
+    /// Whether the reference is only evaluated if
+    /// `is_def_var` returned `true` for the given name.
+    pub(crate) fn is_guarded_by_is_def_var(&self, reference: Symbol, name: &str) -> bool {
+        self.guards_of(reference)
+            .into_iter()
+            .any(|guard| match guard {
+                Guard::Condition(condition, value) => self.defines_var(condition, value, name),
+                Guard::SwitchArm(..) => false,
+            })
+    }
+
+    /// Collect the guards of the symbol from the innermost one,
+    /// guards outside of the closure or function that contains
+    /// the symbol are not included.
+    fn guards_of(&self, symbol: Symbol) -> Vec<Guard> {
+        let sym_data = &self[symbol];
+
+        let (Some(source), Some(range)) = (sym_data.source.source, sym_data.source.text_range)
+        else {
+            return Vec::new();
+        };
+
+        let Some(index) = self.source_indices.get(source) else {
+            return Vec::new();
+        };
+
+        let contains = |sym: Option<Symbol>| {
+            sym.and_then(|sym| self[sym].source.text_range)
+                .is_some_and(|r| r.contains_range(range))
+        };
+
+        let mut containing = index
+            .symbols
+            .containing(range.start(), true)
+            .into_iter()
+            .filter(|(r, sym)| {
+                *sym != symbol && r.contains_range(range) && self.symbols.contains_key(*sym)
+            })
+            .collect::<Vec<_>>();
+        containing.sort_by_key(|(r, _)| r.len());
+
+        let mut guards = Vec::new();
+
+        for (_, sym) in containing {
+            match &self[sym].kind {
+                SymbolKind::Fn(_) | SymbolKind::Closure(_) => break,
+                SymbolKind::If(if_symbol) => {
+                    let Some(branch) = if_symbol.branches.iter().position(|(_, scope)| {
+                        self[*scope]
+                            .source
+                            .text_range
+                            .is_some_and(|r| r.contains_range(range))
+                    }) else {
+                        continue;
+                    };
+
+                    for (idx, (condition, _)) in if_symbol.branches[..=branch].iter().enumerate() {
+                        if let Some(condition) = condition {
+                            guards.push(Guard::Condition(*condition, idx == branch));
+                        }
+                    }
+                }
+                SymbolKind::Binary(binary) if contains(binary.rhs) => {
+                    if let Some(lhs) = binary.lhs {
+                        match binary.lookup_text.as_str() {
+                            "&&" => guards.push(Guard::Condition(lhs, true)),
+                            "||" => guards.push(Guard::Condition(lhs, false)),
+                            _ => {}
+                        }
+                    }
+                }
+                SymbolKind::Switch(switch) => {
+                    if let Some(arm) = switch.arms.iter().position(|arm| contains(arm.value_expr)) {
+                        guards.push(Guard::SwitchArm(sym, arm));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        guards
+    }
+
+    fn guard_narrowings(&self, guard: Guard, target: Symbol) -> Vec<Narrowing> {
+        match guard {
+            Guard::Condition(condition, value) => {
+                self.condition_narrowings(condition, target, value)
+            }
+            Guard::SwitchArm(switch, arm) => self.switch_arm_narrowings(switch, arm, target),
+        }
+    }
+
+    fn condition_narrowings(
+        &self,
+        condition: Symbol,
+        target: Symbol,
+        value: bool,
+    ) -> Vec<Narrowing> {
+        match &self[condition].kind {
+            SymbolKind::Binary(binary) => {
+                let (Some(lhs), Some(rhs)) = (binary.lhs, binary.rhs) else {
+                    return Vec::new();
+                };
+
+                match (binary.lookup_text.as_str(), value) {
+                    ("&&", true) | ("||", false) => {
+                        let mut narrowings = self.condition_narrowings(lhs, target, value);
+                        narrowings.extend(self.condition_narrowings(rhs, target, value));
+                        narrowings
+                    }
+                    (op @ ("==" | "!="), value) => {
+                        let equal = (op == "==") == value;
+
+                        self.comparison_narrowing(lhs, rhs, target, equal)
+                            .or_else(|| self.comparison_narrowing(rhs, lhs, target, equal))
+                            .into_iter()
+                            .collect()
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            SymbolKind::Unary(unary) if unary.lookup_text == "!" => unary
+                .rhs
+                .map(|rhs| self.condition_narrowings(rhs, target, !value))
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn comparison_narrowing(
+        &self,
+        subject: Symbol,
+        other: Symbol,
+        target: Symbol,
+        equal: bool,
+    ) -> Option<Narrowing> {
+        let SymbolKind::Lit(lit) = &self[other].kind else {
+            return None;
+        };
+
+        let subject = self.narrowing_subject(subject, target)?;
+        let test = TypeTest::for_subject(subject, &lit.value)?;
+
+        // Only `()` has a single value, other literals cannot exclude their type.
+        if !equal && subject == Subject::Value && test != TypeTest::Unit {
+            return None;
+        }
+
+        Some(Narrowing {
+            test,
+            negated: !equal,
+        })
+    }
+
+    fn switch_arm_narrowings(&self, switch: Symbol, arm: usize, target: Symbol) -> Vec<Narrowing> {
+        let SymbolKind::Switch(switch) = &self[switch].kind else {
+            return Vec::new();
+        };
+
+        let Some(subject) = switch
+            .target
+            .and_then(|t| self.narrowing_subject(t, target))
+        else {
+            return Vec::new();
+        };
+
+        let arm_test = |arm: &SwitchArm| match &self[arm.pat_expr?].kind {
+            SymbolKind::Lit(lit) => TypeTest::for_subject(subject, &lit.value),
+            _ => None,
+        };
+
+        let current = &switch.arms[arm];
+
+        if let Some(test) = arm_test(current) {
+            return Vec::from([Narrowing {
+                test,
+                negated: false,
+            }]);
+        }
+
+        if !current
+            .pat_expr
+            .is_some_and(|pat| self[pat].kind.is_discard())
+        {
+            return Vec::new();
+        }
+
+        // None of the previous unconditional arms were selected.
+        switch.arms[..arm]
+            .iter()
+            .filter(|arm| arm.condition_expr.is_none())
+            .filter_map(arm_test)
+            .filter(|test| subject == Subject::TypeOf || *test == TypeTest::Unit)
+            .map(|test| Narrowing {
+                test,
+                negated: true,
+            })
+            .collect()
+    }
+
+    fn narrowing_subject(&self, symbol: Symbol, target: Symbol) -> Option<Subject> {
+        let is_target = |sym: Symbol| {
+            matches!(
+                &self[sym].kind,
+                SymbolKind::Ref(r) if matches!(r.target, Some(ReferenceTarget::Symbol(t)) if t == target)
+            )
+        };
+
+        if is_target(symbol) {
+            return Some(Subject::Value);
+        }
+
+        let call = self[symbol].kind.as_call()?;
+
+        match (&self[call.lhs?].kind, call.arguments.as_slice()) {
+            (SymbolKind::Ref(r), &[arg]) if r.name == "type_of" && is_target(arg) => {
+                Some(Subject::TypeOf)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the condition evaluating to the given value
+    /// means that `is_def_var` returned `true` for the name.
+    fn defines_var(&self, condition: Symbol, value: bool, name: &str) -> bool {
+        match &self[condition].kind {
+            SymbolKind::Binary(binary) => match (binary.lookup_text.as_str(), value) {
+                ("&&", true) | ("||", false) => [binary.lhs, binary.rhs]
+                    .into_iter()
+                    .flatten()
+                    .any(|sym| self.defines_var(sym, value, name)),
+                _ => false,
+            },
+            SymbolKind::Unary(unary) if unary.lookup_text == "!" => unary
+                .rhs
+                .is_some_and(|rhs| self.defines_var(rhs, !value, name)),
+            SymbolKind::Call(call) if value => {
+                let is_def_var = call.lhs.is_some_and(
+                    |f| matches!(&self[f].kind, SymbolKind::Ref(r) if r.name == "is_def_var"),
+                );
+
+                is_def_var
+                    && matches!(
+                        call.arguments.as_slice(),
+                        &[arg] if matches!(
+                            &self[arg].kind,
+                            SymbolKind::Lit(lit) if lit.value.as_string().is_some_and(|n| n == name)
+                        )
+                    )
+            }
+            _ => false,
+        }
+    }
+
+    fn narrow(&mut self, ty: Type, narrowing: &Narrowing, source: SourceInfo) -> Type {
+        let members = match &self[ty].kind {
+            TypeKind::Union(tys) => tys.iter().copied().collect(),
+            TypeKind::Alias(_, inner) if self[*inner].kind.is_union() => self[*inner]
+                .kind
+                .as_union()
+                .unwrap()
+                .iter()
+                .copied()
+                .collect(),
+            TypeKind::Unknown => Vec::new(),
+            _ => Vec::from([ty]),
+        };
+
+        let count = members.len();
+
+        let (matching, rest): (Vec<_>, Vec<_>) = members
+            .into_iter()
+            .partition(|&member| narrowing.test.matches(self, member));
+
+        let narrowed = if narrowing.negated { rest } else { matching };
+
+        match narrowed.len() {
+            0 if narrowing.negated => ty,
+            0 => narrowing.test.ty(self).unwrap_or(ty),
+            1 => narrowed[0],
+            len if len == count => ty,
+            _ => self.types.insert(TypeData {
+                source,
+                kind: TypeKind::Union(narrowed.into_iter().collect::<IndexSet<_>>()),
+                protected: false,
+            }),
+        }
+    }
+}
@@ -1,15 +1,18 @@
 use crate::{
     eval::Value,
     hir::BuiltinTypes,
+    scope::ScopeParent,
+    source::{Source, SourceInfo},
     symbol::{ReferenceTarget, SymbolKind},
     ty::{Array, Function, Object, Type, TypeData},
-    HashSet, Hir, IndexMap, IndexSet, Symbol, TypeKind,
+    HashSet, Hir, IndexMap, IndexSet, Scope, Symbol, TypeKind,
 };
+use rhai_rowan::TextRange;
 use slotmap::SlotMap;
 
 impl Hir {
-    pub(crate) fn resolve_types_for_all_symbols(&mut self) {
-        let symbols = self.symbols.keys().collect::<Vec<_>>();
+    pub(crate) fn resolve_types_for_symbols(&mut self, sources: Option<&HashSet<Source>>) {
+        let symbols = self.symbol_keys_in_sources(sources);
 
         let mut seen = HashSet::with_capacity(symbols.len());
         for symbol in symbols {
@@ -17,8 +20,8 @@ impl Hir {
         }
     }
 
-    pub(crate) fn resolve_type_aliases(&mut self) {
-        let symbols = self.symbols.keys().collect::<Vec<_>>();
+    pub(crate) fn resolve_type_aliases(&mut self, sources: Option<&HashSet<Source>>) {
+        let symbols = self.symbol_keys_in_sources(sources);
 
         let mut to_remove = HashSet::with_capacity(symbols.len());
 
@@ -139,14 +142,21 @@ impl Hir {
                     Value::Bool(_) => self.builtin_types.bool,
                     Value::String(_) => self.builtin_types.string,
                     Value::Char(_) => self.builtin_types.char,
-                    Value::Unknown => self.builtin_types.unknown,
+                    Value::Unit => self.builtin_types.void,
+                    Value::Array(_) | Value::Map(_) | Value::Unknown => self.builtin_types.unknown,
                 }
             }
             SymbolKind::Ref(r) => match r.target {
                 Some(ReferenceTarget::Symbol(target_sym)) => {
                     self.resolve_type_for_symbol(seen, target_sym);
-                    let target_sym_data = self.symbols.get(target_sym).unwrap();
-                    self.symbols.get_mut(symbol).unwrap().ty = target_sym_data.ty;
+                    let target_ty = self.symbols.get(target_sym).unwrap().ty;
+                    let ty = self.narrowed_type(symbol, target_sym, target_ty);
+                    let sym_data = self.symbols.get_mut(symbol).unwrap();
+                    sym_data.ty = ty;
+
+                    if let SymbolKind::Ref(r) = &mut sym_data.kind {
+                        r.narrowed = ty != target_ty;
+                    }
                 }
                 Some(ReferenceTarget::Module(_)) => {
                     sym_data.ty = self.builtin_types.module;
@@ -154,16 +164,27 @@ impl Hir {
                 None => sym_data.ty = self.builtin_types.unknown,
             },
             SymbolKind::Decl(decl) => {
+                let mut is_inferred = decl.ty_decl.is_none();
+                let references = decl.references.iter().copied().collect::<Vec<_>>();
+
                 let ty = if let Some(ty) = decl.ty_decl {
                     ty
                 } else if let Some(val) = decl.value {
                     self.resolve_type_for_symbol(seen, val);
                     self.symbols.get(val).unwrap().ty
+                } else if let Some(ty) = self.declared_param_ty(seen, symbol) {
+                    is_inferred = false;
+                    ty
                 } else {
                     self.builtin_types.unknown
                 };
 
                 self.symbols.get_mut(symbol).unwrap().ty = ty;
+
+                if is_inferred {
+                    let ty = self.with_assigned_fields(seen, ty, references, source);
+                    self.symbols.get_mut(symbol).unwrap().ty = ty;
+                }
             }
             SymbolKind::Block(block) => {
                 if let Some(last_symbol) = self
@@ -236,6 +257,8 @@ impl Hir {
             SymbolKind::Fn(f) => {
                 let scope = f.scope;
                 let is_def = f.is_def;
+                let type_params = f.type_params.clone();
+                let definition = f.definition;
 
                 let ret_ty = if is_def && f.ret_ty == self.builtin_types.unknown {
                     self.builtin_types.void
@@ -269,19 +292,10 @@ impl Hir {
 
                 let ret = if is_def {
                     ret_ty
-                } else if let Some(last_expr) = self
-                    .scopes
-                    .get(scope)
-                    .unwrap()
-                    .symbols
-                    .iter()
-                    .copied()
-                    .find(|&sym| !self.symbols.get(sym).unwrap().is_param())
-                {
-                    self.resolve_type_for_symbol(seen, last_expr);
-                    self.symbols.get(last_expr).unwrap().ty
+                } else if let Some(ret_ty) = self.declared_ret_ty(definition) {
+                    ret_ty
                 } else {
-                    self.builtin_types.unknown
+                    self.script_fn_ret_ty(seen, scope, source)
                 };
 
                 self.symbols.get_mut(symbol).unwrap().ty = self.types.insert(TypeData {
@@ -289,6 +303,7 @@ impl Hir {
                     protected: false,
                     kind: TypeKind::Fn(Function {
                         is_closure: false,
+                        type_params,
                         params,
                         ret,
                     }),
@@ -340,7 +355,8 @@ impl Hir {
                     source,
                     protected: false,
                     kind: TypeKind::Fn(Function {
-                        is_closure: false,
+                        is_closure: true,
+                        type_params: Vec::new(),
                         params,
                         ret,
                     }),
@@ -348,13 +364,25 @@ impl Hir {
             }
             SymbolKind::Call(call) => {
                 if let Some(lhs) = call.lhs {
+                    let arguments = call.arguments.clone();
                     self.resolve_type_for_symbol(seen, lhs);
-                    let ty_data = self.types.get(self.symbols.get(lhs).unwrap().ty).unwrap();
+                    let fn_ty = self.symbols.get(lhs).unwrap().ty;
 
-                    let ty = if let Some(ty_fn) = ty_data.kind.as_fn() {
-                        ty_fn.ret
+                    let ty = if let Some(target) = self.fn_pointer_target(symbol) {
+                        self.resolve_type_for_symbol(seen, target);
+                        self[target].ty
+                    } else if let Some((pointer, method)) = self.fn_pointer_method(symbol) {
+                        let is_curry = method == "curry";
+                        self.resolve_type_for_symbol(seen, pointer);
+                        let pointer_ty = self[pointer].ty;
+
+                        if is_curry {
+                            self.curried_fn_ty(pointer_ty, arguments.len(), source)
+                        } else {
+                            self.call_ret_ty(seen, pointer_ty, &arguments, source)
+                        }
                     } else {
-                        self.builtin_types.unknown
+                        self.call_ret_ty(seen, fn_ty, &arguments, source)
                     };
 
                     self.symbols.get_mut(symbol).unwrap().ty = ty;
@@ -413,6 +441,12 @@ impl Hir {
                     .filter_map(|(name, field)| field.value.map(|val| (name.clone(), val)))
                     .collect::<Vec<_>>();
 
+                let field_symbols = o
+                    .fields
+                    .iter()
+                    .map(|(name, field)| (name.clone(), field.symbol))
+                    .collect::<IndexMap<_, _>>();
+
                 for (_, field) in &fields {
                     self.resolve_type_for_symbol(seen, *field);
                 }
@@ -424,10 +458,20 @@ impl Hir {
 
                 self.symbols.get_mut(symbol).unwrap().ty = self.types.insert(TypeData {
                     source,
-                    kind: TypeKind::Object(Object { fields }),
+                    kind: TypeKind::Object(Object {
+                        fields,
+                        field_symbols,
+                        docs: IndexMap::default(),
+                    }),
                     protected: false,
                 });
             }
+            SymbolKind::Field(field) => {
+                if let Some(value) = field.value {
+                    self.resolve_type_for_symbol(seen, value);
+                    self.symbols.get_mut(symbol).unwrap().ty = self.symbols.get(value).unwrap().ty;
+                }
+            }
 
             SymbolKind::Path(p) => {
                 if let Some(&path_sym) = p.segments.last() {
@@ -441,14 +485,39 @@ impl Hir {
                 let lookup_text = b.lookup_text.clone();
 
                 let ty = if b.is_field_access() {
-                    lhs.map(|lhs| {
-                        self.resolve_type_for_symbol(seen, lhs);
-                        lhs
-                    })
-                    .and_then(|lhs| self[self[lhs].ty].kind.as_object())
-                    .and_then(|object| Some((object, rhs.and_then(|rhs| self[rhs].name(self))?)))
-                    .and_then(|(object, field_name)| object.fields.get(field_name))
-                    .copied()
+                    let field = lhs
+                        .map(|lhs| {
+                            self.resolve_type_for_symbol(seen, lhs);
+                            lhs
+                        })
+                        .and_then(|lhs| self.object_of(self[lhs].ty))
+                        .and_then(|object| {
+                            let field_name = rhs.and_then(|rhs| self[rhs].name(self))?;
+                            Some((
+                                object.fields.get(field_name).copied(),
+                                object.field_symbols.get(field_name).copied(),
+                            ))
+                        });
+
+                    let (field_ty, field_symbol) = field.unwrap_or_default();
+
+                    if let Some(rhs) = rhs.filter(|&rhs| self[rhs].kind.is_reference()) {
+                        // The field reference has the type of the field.
+                        self.symbols.get_mut(rhs).unwrap().ty =
+                            field_ty.unwrap_or(self.builtin_types.unknown);
+
+                        if let Some(field_symbol) = field_symbol {
+                            if let Some(r) = self.symbol_mut(rhs).kind.as_reference_mut() {
+                                r.target = Some(ReferenceTarget::Symbol(field_symbol));
+                            }
+
+                            if let Some(f) = self.symbol_mut(field_symbol).kind.as_field_mut() {
+                                f.references.insert(rhs);
+                            }
+                        }
+                    }
+
+                    field_ty
                 } else {
                     match (lhs, rhs) {
                         (Some(lhs), Some(rhs)) => {
@@ -458,34 +527,16 @@ impl Hir {
                             let lhs_ty = self[lhs].ty;
                             let rhs_ty = self[rhs].ty;
 
-                            // (lhs, rhs, ret)
-                            let mut op_types = self
-                                .symbols
-                                .keys()
-                                .filter_map(|sym| {
-                                    if let Some(op) = self[sym].kind.as_op() {
-                                        if op.name == lookup_text
-                                            && op.lhs_ty.is(self, lhs_ty, false)
-                                            && op.rhs_ty?.is(self, rhs_ty, false)
-                                        {
-                                            Some((op.lhs_ty, op.rhs_ty?, op.ret_ty))
-                                        } else {
-                                            None
-                                        }
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect::<Vec<_>>();
-
-                            let exact_types = op_types
-                                .iter()
-                                .find(|(op_lhs, op_rhs, _)| {
-                                    op_lhs.is(self, lhs_ty, true) && op_rhs.is(self, rhs_ty, true)
-                                })
-                                .copied();
-
-                            exact_types.or_else(|| op_types.pop()).map(|(.., ty)| ty)
+                            let operator =
+                                self.binary_operator_overload(&lookup_text, lhs_ty, rhs_ty);
+
+                            if let Some(b) = self.symbol_mut(symbol).kind.as_binary_mut() {
+                                b.operator = operator;
+                            }
+
+                            operator
+                                .and_then(|op| self[op].kind.as_op())
+                                .map(|op| op.ret_ty)
                         }
                         _ => None,
                     }
@@ -556,6 +607,344 @@ impl Hir {
     }
 }
 
+impl Hir {
+    /// The return type of a script function, the union of the types
+    /// of the last expression and all `return` statements.
+    fn script_fn_ret_ty(
+        &mut self,
+        seen: &mut HashSet<Symbol>,
+        scope: Scope,
+        source: SourceInfo,
+    ) -> Type {
+        let last_expr = self[scope]
+            .symbols
+            .iter()
+            .copied()
+            .rfind(|&sym| !self[sym].is_param());
+
+        let descendants = self.descendant_symbols(scope).collect::<Vec<_>>();
+
+        // Returns in closures belong to the closure.
+        let closure_ranges = descendants
+            .iter()
+            .filter(|&&sym| self[sym].kind.is_closure())
+            .filter_map(|&sym| self[sym].source.text_range)
+            .collect::<Vec<_>>();
+
+        let returns = descendants
+            .iter()
+            .filter_map(|&sym| {
+                let ret = self[sym].kind.as_return()?;
+                let range = self[sym].source.text_range?;
+
+                (!closure_ranges.iter().any(|r| r.contains_range(range))).then_some(ret.expr)
+            })
+            .collect::<Vec<_>>();
+
+        if last_expr.is_none() && returns.is_empty() {
+            return self.builtin_types.unknown;
+        }
+
+        let mut types = IndexSet::default();
+
+        for expr in last_expr.map(Some).into_iter().chain(returns) {
+            let ty = match expr {
+                Some(expr) => {
+                    self.resolve_type_for_symbol(seen, expr);
+                    self[expr].ty
+                }
+                None => self.builtin_types.void,
+            };
+
+            if !self[ty].kind.is_never() {
+                types.insert(ty);
+            }
+        }
+
+        if types.is_empty() {
+            self.builtin_types.never
+        } else if types.len() == 1 {
+            types.pop().unwrap()
+        } else {
+            self.types.insert(TypeData {
+                source,
+                kind: TypeKind::Union(types),
+                protected: false,
+            })
+        }
+    }
+
+    /// The type of a script function parameter that is
+    /// declared in the definition of the function.
+    fn declared_param_ty(&mut self, seen: &mut HashSet<Symbol>, param: Symbol) -> Option<Type> {
+        let parent_scope = self.scopes.get(self[param].parent_scope)?;
+        let Some(ScopeParent::Symbol(f)) = parent_scope.parent else {
+            return None;
+        };
+
+        let f = self[f].kind.as_fn()?;
+        let index = f.params(self).position(|p| p == param)?;
+        let definition = self.symbols.get(f.definition?)?.kind.as_fn()?;
+        let declared_param = definition.params(self).nth(index)?;
+
+        self.resolve_type_for_symbol(seen, declared_param);
+
+        Some(self[declared_param].ty).filter(|&ty| ty != self.builtin_types.unknown)
+    }
+
+    /// The return type in the definition of a script function.
+    fn declared_ret_ty(&self, definition: Option<Symbol>) -> Option<Type> {
+        let definition = self.symbols.get(definition?)?.kind.as_fn()?;
+        Some(definition.ret_ty).filter(|&ty| ty != self.builtin_types.unknown)
+    }
+
+    /// Extend an inferred object map shape with the
+    /// fields assigned through the given references,
+    /// e.g. `cfg.tls = true` or `cfg["tls"] = true`.
+    fn with_assigned_fields(
+        &mut self,
+        seen: &mut HashSet<Symbol>,
+        ty: Type,
+        mut references: Vec<Symbol>,
+        source: SourceInfo,
+    ) -> Type {
+        let Some(object) = self[ty].kind.as_object() else {
+            return ty;
+        };
+
+        let mut fields = object.fields.clone();
+        let field_symbols = object.field_symbols.clone();
+        let docs = object.docs.clone();
+
+        references.sort_by_key(|&r| self[r].source.text_range.map(TextRange::start));
+
+        let assigned = references
+            .into_iter()
+            .filter_map(|r| {
+                self.assigned_field(r)
+                    .map(|(name, value)| (name.to_string(), value))
+            })
+            .filter(|(name, _)| !fields.contains_key(name))
+            .collect::<Vec<_>>();
+
+        if assigned.is_empty() {
+            return ty;
+        }
+
+        for (name, value) in assigned {
+            let value_ty = match value {
+                Some(value) => {
+                    self.resolve_type_for_symbol(seen, value);
+                    self[value].ty
+                }
+                None => self.builtin_types.unknown,
+            };
+
+            fields.entry(name).or_insert(value_ty);
+        }
+
+        self.types.insert(TypeData {
+            source,
+            kind: TypeKind::Object(Object {
+                fields,
+                field_symbols,
+                docs,
+            }),
+            protected: false,
+        })
+    }
+
+    /// The return type of a call, type variables of generic functions
+    /// are replaced by the types of the arguments.
+    fn call_ret_ty(
+        &mut self,
+        seen: &mut HashSet<Symbol>,
+        fn_ty: Type,
+        arguments: &[Symbol],
+        source: SourceInfo,
+    ) -> Type {
+        let Some(f) = self[fn_ty].kind.as_fn() else {
+            return self.builtin_types.unknown;
+        };
+
+        if f.type_params.is_empty() {
+            return f.ret;
+        }
+
+        let params = f.params.iter().map(|(_, ty)| *ty).collect::<Vec<_>>();
+        let ret = f.ret;
+
+        for &arg in arguments {
+            self.resolve_type_for_symbol(seen, arg);
+        }
+
+        let arg_types = arguments
+            .iter()
+            .map(|&arg| self[arg].ty)
+            .collect::<Vec<_>>();
+
+        let mut bindings = IndexMap::default();
+
+        for (param_ty, arg_ty) in params.into_iter().zip(arg_types) {
+            self.bind_type_vars(param_ty, arg_ty, &mut bindings);
+        }
+
+        self.instantiate_type(ret, &bindings, source)
+    }
+
+    /// The type of a function pointer with the given amount
+    /// of leading parameters bound by `curry`.
+    fn curried_fn_ty(&mut self, fn_ty: Type, curried: usize, source: SourceInfo) -> Type {
+        let Some(f) = self[fn_ty].kind.as_fn() else {
+            return self.builtin_types.unknown;
+        };
+
+        let f = Function {
+            is_closure: true,
+            type_params: f.type_params.clone(),
+            params: f.params.iter().skip(curried).cloned().collect(),
+            ret: f.ret,
+        };
+
+        self.types.insert(TypeData {
+            source,
+            protected: false,
+            kind: TypeKind::Fn(f),
+        })
+    }
+
+    /// Match a type that contains type variables against a concrete type
+    /// and collect the types of the variables.
+    fn bind_type_vars(&self, ty: Type, concrete: Type, bindings: &mut IndexMap<String, Type>) {
+        match (&self[ty].kind, &self[concrete].kind) {
+            (_, TypeKind::Unknown) => {}
+            (TypeKind::Var(name), _) => {
+                bindings.entry(name.clone()).or_insert(concrete);
+            }
+            (_, TypeKind::Alias(_, concrete)) => self.bind_type_vars(ty, *concrete, bindings),
+            (TypeKind::Array(arr), TypeKind::Array(concrete_arr)) => {
+                self.bind_type_vars(arr.items, concrete_arr.items, bindings);
+            }
+            (TypeKind::Tuple(tys), TypeKind::Tuple(concrete_tys)) => {
+                for (ty, concrete) in tys.iter().zip(concrete_tys) {
+                    self.bind_type_vars(*ty, *concrete, bindings);
+                }
+            }
+            (TypeKind::Object(obj), TypeKind::Object(concrete_obj)) => {
+                for (name, ty) in &obj.fields {
+                    if let Some(concrete) = concrete_obj.fields.get(name) {
+                        self.bind_type_vars(*ty, *concrete, bindings);
+                    }
+                }
+            }
+            (TypeKind::Fn(f), TypeKind::Fn(concrete_f)) => {
+                for ((_, ty), (_, concrete)) in f.params.iter().zip(&concrete_f.params) {
+                    self.bind_type_vars(*ty, *concrete, bindings);
+                }
+
+                self.bind_type_vars(f.ret, concrete_f.ret, bindings);
+            }
+            _ => {}
+        }
+    }
+
+    /// Replace the type variables in the type, new types are
+    /// only created if the type contains type variables.
+    ///
+    /// Type variables without a known type are replaced with the unknown type.
+    fn instantiate_type(
+        &mut self,
+        ty: Type,
+        bindings: &IndexMap<String, Type>,
+        source: SourceInfo,
+    ) -> Type {
+        let kind = match self[ty].kind.clone() {
+            TypeKind::Var(name) => {
+                return bindings
+                    .get(&name)
+                    .copied()
+                    .unwrap_or(self.builtin_types.unknown);
+            }
+            TypeKind::Array(arr) => {
+                let items = self.instantiate_type(arr.items, bindings, source);
+
+                if items == arr.items {
+                    return ty;
+                }
+
+                TypeKind::Array(Array { items })
+            }
+            TypeKind::Tuple(tys) => {
+                let new_tys = tys
+                    .iter()
+                    .map(|&ty| self.instantiate_type(ty, bindings, source))
+                    .collect::<Vec<_>>();
+
+                if new_tys == tys {
+                    return ty;
+                }
+
+                TypeKind::Tuple(new_tys)
+            }
+            TypeKind::Object(obj) => {
+                let fields = obj
+                    .fields
+                    .iter()
+                    .map(|(name, &ty)| (name.clone(), self.instantiate_type(ty, bindings, source)))
+                    .collect::<IndexMap<_, _>>();
+
+                if fields == obj.fields {
+                    return ty;
+                }
+
+                TypeKind::Object(Object {
+                    fields,
+                    field_symbols: obj.field_symbols,
+                    docs: obj.docs,
+                })
+            }
+            TypeKind::Union(tys) => {
+                let new_tys = tys
+                    .iter()
+                    .map(|&ty| self.instantiate_type(ty, bindings, source))
+                    .collect::<IndexSet<_>>();
+
+                if new_tys == tys {
+                    return ty;
+                }
+
+                TypeKind::Union(new_tys)
+            }
+            TypeKind::Fn(f) => {
+                let params = f
+                    .params
+                    .iter()
+                    .map(|(name, ty)| (name.clone(), self.instantiate_type(*ty, bindings, source)))
+                    .collect::<Vec<_>>();
+                let ret = self.instantiate_type(f.ret, bindings, source);
+
+                if ret == f.ret && params == f.params {
+                    return ty;
+                }
+
+                TypeKind::Fn(Function {
+                    is_closure: f.is_closure,
+                    type_params: Vec::new(),
+                    params,
+                    ret,
+                })
+            }
+            _ => return ty,
+        };
+
+        self.types.insert(TypeData {
+            source,
+            kind,
+            protected: false,
+        })
+    }
+}
+
 fn resolve_and_replace(
     types: &mut SlotMap<Type, TypeData>,
     builtin_types: BuiltinTypes,
@@ -624,5 +1013,31 @@ fn resolve_and_replace(
         }
     } else {
         *ty = builtin_types.unknown;
+        return;
+    }
+
+    // Types nested in unions, arrays, tuples and objects.
+    let mut nested = match &types[*ty].kind {
+        TypeKind::Union(tys) => tys.iter().copied().collect(),
+        TypeKind::Array(arr) => Vec::from([arr.items]),
+        TypeKind::Tuple(tys) => tys.clone(),
+        TypeKind::Object(obj) => obj.fields.values().copied().collect(),
+        _ => return,
+    };
+
+    for nested_ty in &mut nested {
+        resolve_and_replace(types, builtin_types, nested_ty, to_remove, visible_types);
+    }
+
+    match &mut types[*ty].kind {
+        TypeKind::Union(tys) => *tys = nested.into_iter().collect(),
+        TypeKind::Array(arr) => arr.items = nested[0],
+        TypeKind::Tuple(tys) => *tys = nested,
+        TypeKind::Object(obj) => {
+            for (field_ty, nested_ty) in obj.fields.values_mut().zip(nested) {
+                *field_ty = nested_ty;
+            }
+        }
+        _ => {}
     }
 }
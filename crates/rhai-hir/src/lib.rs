@@ -11,9 +11,13 @@
     clippy::too_many_arguments
 )]
 
+pub mod builtin;
+pub mod docs;
 pub mod error;
 pub mod eval;
+pub mod export;
 pub mod hir;
+pub mod metadata;
 pub mod module;
 pub mod scope;
 pub mod source;
@@ -0,0 +1,236 @@
+//! Loading of function metadata exported by a Rhai engine.
+//!
+//! Rhai can export the metadata of all registered functions
+//! with `Engine::gen_fn_metadata_to_json`, the functions are added to
+//! the static module, functions of registered static modules are added to
+//! modules with the same name.
+//!
+//! The metadata is converted into definitions and added as
+//! definition sources, so the functions behave exactly like ones
+//! written by hand in `.d.rhai` files.
+
+use crate::Hir;
+use rhai_rowan::{parser::Parser, util::is_valid_ident};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use url::Url;
+
+/// The output of `Engine::gen_fn_metadata_to_json`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+    #[serde(default)]
+    pub modules: crate::IndexMap<String, ModuleMetadata>,
+    #[serde(default)]
+    pub functions: Vec<FnMetadata>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleMetadata {
+    #[serde(default)]
+    pub doc: Option<String>,
+    #[serde(default)]
+    pub modules: crate::IndexMap<String, ModuleMetadata>,
+    #[serde(default)]
+    pub functions: Vec<FnMetadata>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FnMetadata {
+    pub name: String,
+    #[serde(default)]
+    pub access: Option<String>,
+    #[serde(default)]
+    pub params: Vec<ParamMetadata>,
+    #[serde(default)]
+    pub return_type: Option<String>,
+    #[serde(default)]
+    pub doc_comments: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParamMetadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(rename = "type", default)]
+    pub ty: Option<String>,
+}
+
+impl Metadata {
+    /// Create definition sources from the metadata,
+    /// one for the static module and one for each registered module.
+    ///
+    /// Nested modules are not supported and are skipped.
+    #[must_use]
+    pub fn to_definitions(&self) -> Vec<(Option<&str>, String)> {
+        let mut defs = Vec::from([(None, definition("static", None, &self.functions))]);
+
+        for (name, module) in &self.modules {
+            if !is_valid_ident(name) {
+                tracing::debug!(%name, "skipping module with invalid name");
+                continue;
+            }
+
+            if !module.modules.is_empty() {
+                tracing::debug!(%name, "skipping nested modules");
+            }
+
+            defs.push((
+                Some(name.as_str()),
+                definition(name, module.doc.as_deref(), &module.functions),
+            ));
+        }
+
+        defs
+    }
+}
+
+impl Hir {
+    /// Add the functions from the metadata of a Rhai engine,
+    /// replacing any functions previously added from the same URL.
+    pub fn add_metadata(&mut self, url: &Url, metadata: &Metadata) {
+        self.remove_metadata(url);
+
+        for (module, text) in metadata.to_definitions() {
+            let mut source_url = url.clone();
+            source_url.set_fragment(Some(module.unwrap_or("static")));
+
+            self.add_source(&source_url, &Parser::new(&text).parse_def().into_syntax());
+        }
+    }
+
+    /// Remove all functions added from the metadata at the given URL.
+    pub fn remove_metadata(&mut self, url: &Url) {
+        let sources = self
+            .sources()
+            .filter(|(_, data)| {
+                let mut source_url = data.url.clone();
+                source_url.set_fragment(None);
+                data.url.fragment().is_some() && source_url == *url
+            })
+            .map(|(source, _)| source)
+            .collect::<Vec<_>>();
+
+        for source in sources {
+            self.remove_source(source);
+        }
+    }
+}
+
+fn definition(module: &str, docs: Option<&str>, functions: &[FnMetadata]) -> String {
+    let mut def = String::new();
+
+    for line in docs.unwrap_or_default().lines() {
+        writeln!(def, "/// {line}").unwrap();
+    }
+    writeln!(def, "module {module};").unwrap();
+
+    for f in functions {
+        if f.access.as_deref() == Some("private") {
+            continue;
+        }
+
+        let (name, getter) = match f.name.strip_prefix("get$") {
+            Some(name) => (name, true),
+            None => (f.name.as_str(), false),
+        };
+
+        // Setters, indexers and operators cannot be defined this way.
+        if !is_valid_ident(name) {
+            continue;
+        }
+
+        def.push('\n');
+
+        for comment in &f.doc_comments {
+            for line in comment.lines() {
+                writeln!(def, "{}", doc_line(line)).unwrap();
+            }
+        }
+
+        write!(def, "fn {}{name}(", if getter { "get " } else { "" }).unwrap();
+
+        for (i, param) in f.params.iter().enumerate() {
+            if i != 0 {
+                def.push_str(", ");
+            }
+
+            let param_name = param
+                .name
+                .as_deref()
+                .filter(|name| is_valid_ident(name))
+                .map_or_else(|| format!("_{i}"), ToString::to_string);
+
+            write!(
+                def,
+                "{param_name}: {}",
+                rhai_type(param.ty.as_deref().unwrap_or("?"))
+            )
+            .unwrap();
+        }
+
+        def.push(')');
+
+        if let Some(ret) = f.return_type.as_deref() {
+            write!(def, " -> {}", rhai_type(ret)).unwrap();
+        }
+
+        def.push_str(";\n");
+    }
+
+    def
+}
+
+/// Normalize doc comments from the metadata into `///` lines.
+fn doc_line(line: &str) -> String {
+    let line = line.trim();
+
+    if let Some(rest) = line.strip_prefix("///") {
+        return format!("///{rest}");
+    }
+
+    let rest = line
+        .trim_start_matches("/**")
+        .trim_end_matches("*/")
+        .trim_start_matches('*');
+
+    format!("/// {}", rest.trim())
+}
+
+/// Convert a Rust type name from the metadata into a Rhai type.
+fn rhai_type(ty: &str) -> String {
+    let mut ty = ty.trim();
+
+    ty = ty.strip_prefix("&mut ").unwrap_or(ty);
+    ty = ty.strip_prefix('&').unwrap_or(ty);
+    ty = ty.strip_prefix("rhai::").unwrap_or(ty);
+
+    if let Some(inner) = ty
+        .strip_prefix("RhaiResultOf<")
+        .or_else(|| ty.strip_prefix("Result<"))
+        .and_then(|t| t.strip_suffix('>'))
+    {
+        let inner = inner.split_once(',').map_or(inner, |(ok, _)| ok);
+        return rhai_type(inner);
+    }
+
+    match ty {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64" | "u128" | "isize"
+        | "usize" | "INT" => "int".into(),
+        "f32" | "f64" | "FLOAT" | "Decimal" => "float".into(),
+        "bool" => "bool".into(),
+        "char" => "char".into(),
+        "str" | "String" | "ImmutableString" => "String".into(),
+        "Array" | "Vec<Dynamic>" => "[?]".into(),
+        "Blob" | "Vec<u8>" => "Blob".into(),
+        "FnPtr" => "Fn".into(),
+        "Instant" => "timestamp".into(),
+        "()" => "()".into(),
+        "RhaiResult" | "Dynamic" | "Map" | "" => "?".into(),
+        ty if is_valid_ident(ty) => ty.into(),
+        _ => "?".into(),
+    }
+}
@@ -1,11 +1,12 @@
 use std::{ffi::OsStr, path::Path};
 
-use crate::{source::Source, Hir, IndexSet, Scope};
+use crate::{source::Source, Hir, IndexSet, Scope, Symbol};
+use serde::{Deserialize, Serialize};
 use url::Url;
 
 slotmap::new_key_type! { pub struct Module; }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub enum ModuleKind {
     /// The static module is the root of every Rhai script,
     /// items and modules defined in the scope of the static module
@@ -31,7 +32,7 @@ impl core::fmt::Display for ModuleKind {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModuleData {
     pub scope: Scope,
     pub kind: ModuleKind,
@@ -52,6 +53,127 @@ impl ModuleData {
     }
 }
 
+/// A single import edge between two modules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModuleImport {
+    /// The module containing the import statement.
+    pub from: Module,
+    /// The imported module.
+    pub to: Module,
+    /// The import statement.
+    pub import: Symbol,
+}
+
+/// Import relations between modules.
+///
+/// Created by [`Hir::module_graph`].
+#[derive(Debug, Default, Clone)]
+pub struct ModuleGraph {
+    /// All import edges in the order of the import statements.
+    pub edges: Vec<ModuleImport>,
+}
+
+impl ModuleGraph {
+    /// Imports in the given module.
+    pub fn imports_of(&self, module: Module) -> impl Iterator<Item = &ModuleImport> {
+        self.edges.iter().filter(move |edge| edge.from == module)
+    }
+
+    /// Imports of the given module in other modules.
+    pub fn importers_of(&self, module: Module) -> impl Iterator<Item = &ModuleImport> {
+        self.edges.iter().filter(move |edge| edge.to == module)
+    }
+
+    /// All modules that depend on the given module either
+    /// directly or via other modules.
+    ///
+    /// The module itself is only included if it is part of an import cycle.
+    #[must_use]
+    pub fn transitive_dependents(&self, module: Module) -> IndexSet<Module> {
+        let mut dependents = IndexSet::default();
+        let mut stack = vec![module];
+
+        while let Some(m) = stack.pop() {
+            for edge in self.importers_of(m) {
+                if dependents.insert(edge.from) {
+                    stack.push(edge.from);
+                }
+            }
+        }
+
+        dependents
+    }
+
+    /// All modules that the given module depends on either
+    /// directly or via other modules.
+    ///
+    /// The module itself is only included if it is part of an import cycle.
+    #[must_use]
+    pub fn transitive_dependencies(&self, module: Module) -> IndexSet<Module> {
+        let mut dependencies = IndexSet::default();
+        let mut stack = vec![module];
+
+        while let Some(m) = stack.pop() {
+            for edge in self.imports_of(m) {
+                if dependencies.insert(edge.to) {
+                    stack.push(edge.to);
+                }
+            }
+        }
+
+        dependencies
+    }
+
+    /// Import statements that close an import cycle.
+    ///
+    /// Each item contains the import that closes the cycle
+    /// followed by all the other imports in the cycle.
+    #[must_use]
+    pub fn cycles(&self) -> Vec<Vec<Symbol>> {
+        let mut modules: IndexSet<Module> = IndexSet::default();
+
+        for edge in &self.edges {
+            modules.insert(edge.from);
+            modules.insert(edge.to);
+        }
+
+        let mut visited: IndexSet<Module> = IndexSet::default();
+        let mut cycles = Vec::new();
+
+        for &module in &modules {
+            if !visited.contains(&module) {
+                self.find_cycles(module, &mut visited, &mut Vec::new(), &mut cycles);
+            }
+        }
+
+        cycles
+    }
+
+    fn find_cycles<'g>(
+        &'g self,
+        module: Module,
+        visited: &mut IndexSet<Module>,
+        path: &mut Vec<&'g ModuleImport>,
+        cycles: &mut Vec<Vec<Symbol>>,
+    ) {
+        visited.insert(module);
+
+        for edge in self.imports_of(module) {
+            if let Some(start) = path.iter().position(|e| e.from == edge.to) {
+                let mut cycle = vec![edge.import];
+                cycle.extend(path[start..].iter().map(|e| e.import));
+                cycles.push(cycle);
+            } else if edge.to == module {
+                cycles.push(vec![edge.import]);
+            } else if !visited.contains(&edge.to) {
+                path.push(edge);
+                self.find_cycles(edge.to, visited, path, cycles);
+                path.pop();
+            }
+        }
+    }
+}
+
 pub const STATIC_URL_SCHEME: &str = "rhai-static";
 
 /// Used to resolve module URLs for import statements and definitions.
@@ -99,6 +221,24 @@ pub trait ModuleResolver: Send + Sync {
             path,
         )
     }
+
+    /// All the URLs the import path can refer to in order of priority,
+    /// used to find modules that are not loaded yet.
+    ///
+    /// By default this is only the URL returned by `resolve_url_from_module`.
+    ///
+    /// # Errors
+    ///
+    /// This function should only report errors if the import
+    /// path is invalid.
+    fn resolve_candidate_urls_from_module(
+        &self,
+        hir: &Hir,
+        from: Module,
+        path: &str,
+    ) -> anyhow::Result<Vec<Url>> {
+        Ok(vec![self.resolve_url_from_module(hir, from, path)?])
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -118,3 +258,208 @@ impl ModuleResolver for DefaultModuleResolver {
         }
     }
 }
+
+/// A module resolver that mirrors Rhai's `FileModuleResolver`
+/// with additional search paths and aliases.
+///
+/// Import paths are resolved from the base URL, or relative to the importing
+/// module if there is none. Paths that do not start with `./` or `../`
+/// are then also resolved from the search URLs in order, the first module
+/// that is known to the HIR is used.
+///
+/// Without any configuration it behaves the same as [`DefaultModuleResolver`].
+#[derive(Debug, Clone)]
+pub struct FileModuleResolver {
+    base_url: Option<Url>,
+    search_urls: Vec<Url>,
+    aliases: Vec<(String, Url)>,
+    extension: String,
+}
+
+impl Default for FileModuleResolver {
+    fn default() -> Self {
+        Self {
+            base_url: None,
+            search_urls: Vec::new(),
+            aliases: Vec::new(),
+            extension: String::from("rhai"),
+        }
+    }
+}
+
+impl FileModuleResolver {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a resolver from configured module locations,
+    /// e.g. the `modules` section of `Rhai.toml`.
+    #[must_use]
+    pub fn from_config<'a>(
+        base_url: Option<Url>,
+        search_urls: impl IntoIterator<Item = Url>,
+        aliases: impl IntoIterator<Item = (&'a str, Url)>,
+        extension: Option<&str>,
+    ) -> Self {
+        let mut resolver = Self::new();
+
+        if let Some(url) = base_url {
+            resolver = resolver.with_base_url(url);
+        }
+
+        for url in search_urls {
+            resolver = resolver.with_search_url(url);
+        }
+
+        for (alias, url) in aliases {
+            resolver = resolver.with_alias(alias, url);
+        }
+
+        if let Some(extension) = extension {
+            resolver = resolver.with_extension(extension);
+        }
+
+        resolver
+    }
+
+    /// The directory that import paths are resolved from
+    /// instead of the directory of the importing module.
+    #[must_use]
+    pub fn with_base_url(mut self, url: Url) -> Self {
+        self.base_url = Some(directory_url(url));
+        self
+    }
+
+    /// An additional directory that is searched for modules.
+    #[must_use]
+    pub fn with_search_url(mut self, url: Url) -> Self {
+        self.search_urls.push(directory_url(url));
+        self
+    }
+
+    /// Map an import path or path prefix to a module or directory URL.
+    ///
+    /// For example with an alias from `std` to `file:///lib/std`,
+    /// `import "std/math"` resolves to `file:///lib/std/math.rhai`.
+    #[must_use]
+    pub fn with_alias(mut self, path: impl Into<String>, url: Url) -> Self {
+        self.aliases.push((path.into(), url));
+        self
+    }
+
+    /// The extension of module files that is appended to import
+    /// paths without it, `rhai` by default.
+    #[must_use]
+    pub fn with_extension(mut self, extension: &str) -> Self {
+        self.extension = extension.trim_start_matches('.').to_string();
+        self
+    }
+
+    /// All the URLs the import path can refer to in order of priority,
+    /// the returned list is never empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the import path cannot be joined with the base URLs.
+    pub fn candidate_urls(&self, from: &Url, path: &str) -> anyhow::Result<Vec<Url>> {
+        if let Ok(url) = Url::parse(path) {
+            return Ok(vec![url]);
+        }
+
+        for (alias, target) in &self.aliases {
+            if path == alias {
+                return Ok(vec![self.url_with_extension(target.clone())]);
+            }
+
+            if let Some(rest) = path
+                .strip_prefix(alias.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                let url = directory_url(target.clone()).join(&self.path_with_extension(rest))?;
+                return Ok(vec![url]);
+            }
+        }
+
+        let file_path = self.path_with_extension(path);
+        let base_url = self.base_url.as_ref().unwrap_or(from);
+
+        // Rhai resolves these from the base path as well,
+        // but they are not looked up anywhere else.
+        if path.starts_with("./") || path.starts_with("../") {
+            return Ok(vec![base_url.join(&file_path)?]);
+        }
+
+        let mut urls = vec![base_url.join(&file_path)?];
+
+        for search_url in &self.search_urls {
+            urls.push(search_url.join(&file_path)?);
+        }
+
+        Ok(urls)
+    }
+
+    fn path_with_extension(&self, path: &str) -> String {
+        if Path::new(path).extension() == Some(OsStr::new(&self.extension)) {
+            path.to_string()
+        } else {
+            format!("{path}.{}", self.extension)
+        }
+    }
+
+    fn url_with_extension(&self, mut url: Url) -> Url {
+        if url.scheme() != STATIC_URL_SCHEME {
+            let path = self.path_with_extension(url.path());
+            url.set_path(&path);
+        }
+
+        url
+    }
+}
+
+impl ModuleResolver for FileModuleResolver {
+    fn resolve_url(&self, from: &Url, path: &str) -> anyhow::Result<Url> {
+        let mut urls = self.candidate_urls(from, path)?;
+        Ok(urls.swap_remove(0))
+    }
+
+    fn resolve_url_from_module(&self, hir: &Hir, from: Module, path: &str) -> anyhow::Result<Url> {
+        let mut urls = self.candidate_urls(
+            hir[from]
+                .url()
+                .ok_or_else(|| anyhow::anyhow!("could not determine base url"))?,
+            path,
+        )?;
+
+        let found = urls
+            .iter()
+            .position(|url| hir.module_by_url(url).is_some())
+            .unwrap_or(0);
+
+        Ok(urls.swap_remove(found))
+    }
+    fn resolve_candidate_urls_from_module(
+        &self,
+        hir: &Hir,
+        from: Module,
+        path: &str,
+    ) -> anyhow::Result<Vec<Url>> {
+        self.candidate_urls(
+            hir[from]
+                .url()
+                .ok_or_else(|| anyhow::anyhow!("could not determine base url"))?,
+            path,
+        )
+    }
+}
+
+/// Directory URLs must end with a slash so that paths are joined
+/// inside the directory instead of replacing the last segment.
+fn directory_url(mut url: Url) -> Url {
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+
+    url
+}
@@ -1,8 +1,9 @@
 use crate::{source::SourceInfo, HashSet, IndexSet, Symbol};
+use serde::{Deserialize, Serialize};
 
 slotmap::new_key_type! { pub struct Scope; }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct ScopeData {
     pub source: SourceInfo,
@@ -32,7 +33,7 @@ impl ScopeData {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ScopeParent {
     Scope(Scope),
     Symbol(Symbol),
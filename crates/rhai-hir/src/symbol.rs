@@ -1,11 +1,12 @@
 use super::module::Module;
-use crate::{eval::Value, source::SourceInfo, ty::Type, HashSet, Hir, IndexMap, Scope};
+use crate::{eval::Value, source::SourceInfo, ty::Type, HashSet, Hir, IndexMap, IndexSet, Scope};
 use rhai_rowan::{syntax::SyntaxKind, TextRange};
+use serde::{Deserialize, Serialize};
 use strum::IntoStaticStr;
 
 slotmap::new_key_type! { pub struct Symbol; }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct SymbolData {
     pub source: SourceInfo,
@@ -22,6 +23,7 @@ impl SymbolData {
             SymbolKind::Fn(f) => Some(&f.name),
             SymbolKind::Decl(d) => Some(&d.name),
             SymbolKind::Ref(r) => Some(&r.name),
+            SymbolKind::Field(f) => Some(&f.name),
             SymbolKind::Import(import) => import.alias.and_then(|alias| hir[alias].name(hir)),
             SymbolKind::Virtual(VirtualSymbol::Module(m)) => Some(&m.name),
             SymbolKind::Virtual(VirtualSymbol::Alias(a)) => Some(&a.name),
@@ -34,10 +36,19 @@ impl SymbolData {
         match &self.kind {
             SymbolKind::Fn(f) => Some(&f.docs),
             SymbolKind::Decl(d) => Some(&d.docs),
+            SymbolKind::Field(f) => Some(&f.docs),
+            SymbolKind::Op(op) => Some(&op.docs),
             _ => None,
         }
     }
 
+    /// The deprecation reason if the symbol is marked as deprecated
+    /// in its docs.
+    #[must_use]
+    pub fn deprecated(&self) -> Option<String> {
+        self.docs().and_then(crate::docs::deprecation)
+    }
+
     /// Whether the given range is the identifier of the symbol.
     #[must_use]
     pub fn has_selection_range(&self, range: TextRange) -> bool {
@@ -89,6 +100,7 @@ impl SymbolData {
             SymbolKind::Ref(r) => r.target,
             SymbolKind::Decl(d) => d.target,
             SymbolKind::Import(i) => i.target.map(ReferenceTarget::Module),
+            SymbolKind::Lit(l) => l.targets.first().copied().map(ReferenceTarget::Symbol),
             SymbolKind::Virtual(VirtualSymbol::Module(m)) => {
                 Some(ReferenceTarget::Module(m.module))
             }
@@ -97,7 +109,7 @@ impl SymbolData {
     }
 }
 
-#[derive(Debug, Clone, IntoStaticStr)]
+#[derive(Debug, Clone, IntoStaticStr, Serialize, Deserialize)]
 pub enum SymbolKind {
     Block(BlockSymbol),
     Fn(FnSymbol),
@@ -111,6 +123,7 @@ pub enum SymbolKind {
     Array(ArraySymbol),
     Index(IndexSymbol),
     Object(ObjectSymbol),
+    Field(FieldSymbol),
     Call(CallSymbol),
     Closure(ClosureSymbol),
     If(IfSymbol),
@@ -165,6 +178,15 @@ impl SymbolKind {
         }
     }
 
+    #[must_use]
+    pub fn as_fn_mut(&mut self) -> Option<&mut FnSymbol> {
+        if let Self::Fn(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
     /// Returns `true` if the symbol kind is [`Op`].
     ///
     /// [`Op`]: SymbolKind::Op
@@ -311,6 +333,15 @@ impl SymbolKind {
         }
     }
 
+    #[must_use]
+    pub fn as_binary_mut(&mut self) -> Option<&mut BinarySymbol> {
+        if let Self::Binary(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
     /// Returns `true` if the symbol kind is [`Array`].
     ///
     /// [`Array`]: SymbolKind::Array
@@ -362,6 +393,32 @@ impl SymbolKind {
         }
     }
 
+    /// Returns `true` if the symbol kind is [`Field`].
+    ///
+    /// [`Field`]: SymbolKind::Field
+    #[must_use]
+    pub fn is_field(&self) -> bool {
+        matches!(self, Self::Field(..))
+    }
+
+    #[must_use]
+    pub fn as_field(&self) -> Option<&FieldSymbol> {
+        if let Self::Field(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_field_mut(&mut self) -> Option<&mut FieldSymbol> {
+        if let Self::Field(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
     /// Returns `true` if the symbol kind is [`Call`].
     ///
     /// [`Call`]: SymbolKind::Call
@@ -396,6 +453,15 @@ impl SymbolKind {
         }
     }
 
+    #[must_use]
+    pub fn as_closure_mut(&mut self) -> Option<&mut ClosureSymbol> {
+        if let Self::Closure(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
     /// Returns `true` if the symbol kind is [`If`].
     ///
     /// [`If`]: SymbolKind::If
@@ -670,12 +736,13 @@ impl SymbolKind {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockSymbol {
     pub scope: Scope,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct FnSymbol {
     pub name: String,
     pub docs: String,
@@ -685,9 +752,36 @@ pub struct FnSymbol {
     pub setter: bool,
     pub is_def: bool,
     pub ret_ty: Type,
+    /// The names of the type parameters in definitions.
+    #[serde(default)]
+    pub type_params: Vec<String>,
+    /// The declaration of a script function in a definition
+    /// file that describes the script.
+    #[serde(default)]
+    pub definition: Option<Symbol>,
+    /// Whether the docs were taken from the [`definition`](Self::definition).
+    #[serde(default)]
+    pub inherited_docs: bool,
 }
 
-#[derive(Debug, Default, Clone)]
+impl FnSymbol {
+    /// Return the parameter declarations of the function.
+    pub fn params<'h>(&self, hir: &'h Hir) -> impl Iterator<Item = Symbol> + 'h {
+        hir[self.scope]
+            .symbols
+            .iter()
+            .copied()
+            .take_while(|&sym| hir[sym].is_param())
+    }
+
+    /// Return the amount of parameters of the function.
+    #[must_use]
+    pub fn arity(&self, hir: &Hir) -> usize {
+        self.params(hir).count()
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct OpSymbol {
     pub name: String,
     pub docs: String,
@@ -698,6 +792,13 @@ pub struct OpSymbol {
 }
 
 impl OpSymbol {
+    /// The deprecation reason if the operator is marked as deprecated
+    /// in its docs.
+    #[must_use]
+    pub fn deprecated(&self) -> Option<String> {
+        crate::docs::deprecation(&self.docs)
+    }
+
     #[allow(clippy::unused_self)]
     #[must_use]
     pub fn signature(&self, hir: &Hir) -> String {
@@ -717,7 +818,7 @@ impl OpSymbol {
 }
 
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct DeclSymbol {
     pub name: String,
     pub docs: String,
@@ -735,40 +836,55 @@ pub struct DeclSymbol {
     pub target: Option<ReferenceTarget>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ReferenceSymbol {
     pub target: Option<ReferenceTarget>,
     pub part_of_path: bool,
     pub field_access: bool,
     pub name: String,
+    /// The type of the target is narrowed at this reference.
+    #[serde(default)]
+    pub narrowed: bool,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct PathSymbol {
     pub scope: Scope,
     pub segments: Vec<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct LitSymbol {
     pub value: Value,
+    /// String segments of a template literal around the interpolations.
+    pub template_parts: Vec<String>,
     pub interpolated_scopes: Vec<Scope>,
+    /// The functions named by the string, if it is passed
+    /// to `Fn` or to a host function that takes function names.
+    ///
+    /// Functions are overloaded by arity, so every function
+    /// with the name is included.
+    #[serde(default)]
+    pub targets: Vec<Symbol>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnarySymbol {
     pub lookup_text: String,
     pub op: Option<SyntaxKind>,
     pub rhs: Option<Symbol>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BinarySymbol {
     pub scope: Scope,
     pub lookup_text: String,
     pub lhs: Option<Symbol>,
     pub op: Option<BinaryOpKind>,
     pub rhs: Option<Symbol>,
+    /// The operator overload that accepts the types of the operands.
+    #[serde(default)]
+    pub operator: Option<Symbol>,
 }
 
 impl BinarySymbol {
@@ -776,9 +892,16 @@ impl BinarySymbol {
     pub fn is_field_access(&self) -> bool {
         self.lookup_text == "."
     }
+
+    /// Whether the operator is an assignment, e.g. `=` or `+=`.
+    #[must_use]
+    pub fn is_assignment(&self) -> bool {
+        self.lookup_text.ends_with('=')
+            && !matches!(self.lookup_text.as_str(), "==" | "!=" | "<=" | ">=")
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BinaryOpKind {
     Regular(SyntaxKind),
     Custom(CustomBinaryOp),
@@ -820,108 +943,134 @@ impl BinaryOpKind {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomBinaryOp {
     pub name: String,
     pub range: TextRange,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArraySymbol {
     pub values: Vec<Symbol>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexSymbol {
     pub base: Option<Symbol>,
     pub index: Option<Symbol>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CallSymbol {
     pub lhs: Option<Symbol>,
     pub arguments: Vec<Symbol>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ObjectSymbol {
     pub fields: IndexMap<String, ObjectField>,
+    /// Fields with a name that already appeared
+    /// earlier in the same object literal.
+    pub duplicate_fields: Vec<ObjectField>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ObjectField {
     pub property_syntax: SourceInfo,
     pub property_name: String,
     pub field_syntax: SourceInfo,
     pub value: Option<Symbol>,
+    /// The [`FieldSymbol`] of the field.
+    pub symbol: Symbol,
+}
+
+/// A field defined in an object map literal
+/// or in an object type of a definition.
+///
+/// Field accesses of values that have the
+/// type of the object reference these symbols.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldSymbol {
+    pub name: String,
+    pub docs: String,
+    /// The value of the field in object map literals.
+    pub value: Option<Symbol>,
+    pub references: HashSet<Symbol>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThrowSymbol {
     pub expr: Option<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ClosureSymbol {
     pub scope: Scope,
     pub expr: Option<Symbol>,
+    /// Variables declared outside of the closure
+    /// that are referenced in its body.
+    ///
+    /// Rhai captures these by shared reference.
+    pub captures: IndexSet<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct IfSymbol {
     /// Conditions and scopes for each branch.
     pub branches: Vec<(Option<Symbol>, Scope)>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct LoopSymbol {
     pub scope: Scope,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ForSymbol {
     pub cursor: Option<Symbol>,
     pub scope: Scope,
+    /// Declarations of the loop variables.
+    pub pat_symbols: Vec<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct WhileSymbol {
     pub condition: Option<Symbol>,
     pub scope: Scope,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BreakSymbol {
     pub expr: Option<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ContinueSymbol {}
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ReturnSymbol {
     pub expr: Option<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SwitchSymbol {
     pub target: Option<Symbol>,
     pub arms: Vec<SwitchArm>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SwitchArm {
     pub pat_expr: Option<Symbol>,
     pub condition_expr: Option<Symbol>,
     pub value_expr: Option<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ExportSymbol {
     pub target: Option<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ImportSymbol {
     pub scope: Scope,
     pub expr: Option<Symbol>,
@@ -937,16 +1086,16 @@ impl ImportSymbol {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TrySymbol {
     pub try_scope: Scope,
     pub catch_scope: Scope,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct DiscardSymbol {}
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ReferenceTarget {
     Symbol(Symbol),
     Module(Module),
@@ -990,7 +1139,7 @@ impl ReferenceTarget {
 
 /// A symbol that does not and cannot originate
 /// from source code and was injected into the hir.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum VirtualSymbol {
     Proxy(VirtualProxySymbol),
     Module(VirtualModuleSymbol),
@@ -1052,14 +1201,14 @@ impl VirtualSymbol {
 }
 
 /// A symbol that points to some other symbols transparently.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct VirtualProxySymbol {
     pub target: Symbol,
 }
 
 /// A symbol that is used to inject modules into scopes
 /// without an import statement.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VirtualModuleSymbol {
     pub name: String,
     pub module: Module,
@@ -1068,13 +1217,13 @@ pub struct VirtualModuleSymbol {
 /// A symbol that points to some other symbol under an alias.
 /// 
 /// Has the same semantics as [`VirtualModuleSymbol`].
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VirtualAliasSymbol {
     pub name: String,
     pub target: Symbol,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypeDeclSymbol {
     pub docs: String,
     pub ty: Type,
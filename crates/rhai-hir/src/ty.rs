@@ -1,6 +1,7 @@
 #![allow(dead_code)]
-use crate::{source::SourceInfo, Hir, IndexMap, IndexSet};
+use crate::{source::SourceInfo, Hir, IndexMap, IndexSet, Symbol};
 use core::fmt;
+use serde::{Deserialize, Serialize};
 
 slotmap::new_key_type! { pub struct Type; }
 
@@ -24,7 +25,9 @@ impl Type {
         let other = &hir[other];
 
         match (&this.kind, &other.kind) {
-            (TypeKind::Unknown, _) | (_, TypeKind::Unknown) => !exact,
+            (TypeKind::Var(v1), TypeKind::Var(v2)) => v1 == v2,
+            (TypeKind::Unknown | TypeKind::Var(_), _)
+            | (_, TypeKind::Unknown | TypeKind::Var(_)) => !exact,
             (TypeKind::Unresolved(ty1), TypeKind::Unresolved(ty2)) => ty1 == ty2,
             (TypeKind::Alias(_, ty1), TypeKind::Alias(_, ty2)) => ty1.is(hir, *ty2, true),
             (TypeKind::Array(arr1), TypeKind::Array(arr2)) => arr1.items.is(hir, arr2.items, true),
@@ -58,7 +61,7 @@ impl Type {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct TypeData {
     pub source: SourceInfo,
@@ -132,8 +135,10 @@ impl core::fmt::Display for TypeFormatter<'_> {
             TypeKind::Fn(func) => {
                 if func.is_closure {
                     f.write_str("|")?;
-                } else {
+                } else if func.type_params.is_empty() {
                     f.write_str("fn (")?;
+                } else {
+                    write!(f, "fn <{}>(", func.type_params.join(", "))?;
                 }
 
                 let mut first = true;
@@ -143,7 +148,11 @@ impl core::fmt::Display for TypeFormatter<'_> {
                     }
                     first = false;
 
-                    write!(f, "{name}: {}", ty.fmt(self.hir))?;
+                    if name.is_empty() {
+                        write!(f, "{}", ty.fmt(self.hir))?;
+                    } else {
+                        write!(f, "{name}: {}", ty.fmt(self.hir))?;
+                    }
                 }
 
                 if func.is_closure {
@@ -156,6 +165,7 @@ impl core::fmt::Display for TypeFormatter<'_> {
             }
             TypeKind::Alias(alias, _) => f.write_str(alias.trim())?,
             TypeKind::Unresolved(ty) => f.write_str(ty.trim())?,
+            TypeKind::Var(name) => f.write_str(name)?,
             TypeKind::Never => f.write_str("!")?,
             TypeKind::Unknown => f.write_str("?")?,
             TypeKind::Primitive(ty) => f.write_str(ty)?,
@@ -165,7 +175,7 @@ impl core::fmt::Display for TypeFormatter<'_> {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TypeKind {
     Module,
     Int,
@@ -181,6 +191,9 @@ pub enum TypeKind {
     Fn(Function),
     Alias(String, Type),
     Unresolved(String),
+    /// A type variable of a generic function, e.g. `T` in
+    /// `fn first<T>(arr: [T]) -> T`, it is replaced at call sites.
+    Var(String),
     Tuple(Vec<Type>),
     /// An arbitrary atomic primitive type.
     Primitive(String),
@@ -337,6 +350,14 @@ impl TypeKind {
         matches!(self, Self::Unresolved(..))
     }
 
+    /// Returns `true` if the type kind is [`Var`].
+    ///
+    /// [`Var`]: TypeKind::Var
+    #[must_use]
+    pub fn is_var(&self) -> bool {
+        matches!(self, Self::Var(..))
+    }
+
     /// Returns `true` if the type kind is [`Never`].
     ///
     /// [`Never`]: TypeKind::Never
@@ -377,19 +398,28 @@ impl Default for TypeKind {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Object {
     pub fields: IndexMap<String, Type>,
+    /// The symbols the fields were defined by, if known.
+    #[serde(default)]
+    pub field_symbols: IndexMap<String, Symbol>,
+    /// Documentation of fields that have any.
+    #[serde(default)]
+    pub docs: IndexMap<String, String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Array {
     pub items: Type,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Function {
     pub is_closure: bool,
+    /// The names of the type variables of generic functions.
+    #[serde(default)]
+    pub type_params: Vec<String>,
     pub params: Vec<(String, Type)>,
     pub ret: Type,
 }
@@ -0,0 +1,61 @@
+use rhai_hir::{builtin::StdPackage, error::ErrorKind, Hir};
+use rhai_rowan::parser::Parser;
+
+#[test]
+fn test_builtin_definitions_parse() {
+    for package in StdPackage::standard() {
+        let parse = Parser::new(package.definitions()).parse_def();
+        assert!(parse.errors.is_empty(), "{package:?}: {:?}", parse.errors);
+    }
+}
+
+#[test]
+fn test_builtin_packages() {
+    let src = r#"
+print(type_of(42));
+let n = len(sub_string("hello", 1, 2));
+let x = parse_int("1");
+let a = [];
+push(a, 1);
+
+for i in range(0, 10) {}
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+    assert!(!hir.errors().is_empty());
+
+    hir.set_std_packages(StdPackage::standard());
+    hir.resolve_all();
+    assert!(hir.errors().is_empty(), "{:?}", hir.errors());
+
+    hir.set_std_packages(StdPackage::CORE.iter().copied());
+    hir.resolve_all();
+
+    let mut unresolved = hir
+        .errors()
+        .into_iter()
+        .filter_map(|error| match error.kind {
+            ErrorKind::UnresolvedReference {
+                reference_symbol, ..
+            } => hir[reference_symbol].name(&hir).map(ToString::to_string),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    unresolved.sort();
+
+    assert_eq!(unresolved, ["parse_int", "push", "sub_string"]);
+
+    // The selection survives clearing the HIR.
+    hir.clear();
+    assert_eq!(hir.std_packages(), StdPackage::CORE);
+    assert!(hir
+        .sources()
+        .any(|(_, data)| data.url == StdPackage::LanguageCore.url()));
+}
@@ -0,0 +1,267 @@
+use rhai_hir::{error::ErrorKind, Hir};
+use rhai_rowan::parser::Parser;
+
+#[test]
+fn test_closure_captures() {
+    let src = r#"
+let a = 1;
+let b = 2;
+let unused = 3;
+
+let f = |x| {
+    let local = x + a;
+    local + b + a
+};
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let closure = hir
+        .symbols()
+        .find_map(|(_, data)| data.kind.as_closure())
+        .unwrap();
+
+    let captures: Vec<&str> = closure
+        .captures
+        .iter()
+        .filter_map(|&sym| hir[sym].name(&hir))
+        .collect();
+
+    assert_eq!(captures, ["a", "b"]);
+}
+
+#[test]
+fn test_closure_captures_loop_variable() {
+    let src = r#"
+let fns = [];
+
+for i in 0..10 {
+    fns.push(|| i);
+}
+
+for j in 0..10 {
+    let k = j;
+    fns.push(|| k);
+}
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let errors = hir.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0].kind,
+        ErrorKind::ClosureCapturesLoopVariable { name, .. } if name == "i"
+    ));
+}
+
+fn decl_ty(hir: &Hir, name: &str) -> String {
+    hir.symbols()
+        .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == name))
+        .map(|(_, data)| data.ty.fmt(hir).to_string())
+        .unwrap()
+}
+
+#[test]
+fn test_closure_call_types() {
+    let src = r#"
+fn greet(name) {
+    "hello"
+}
+
+let f = |x, y| 2.0;
+
+let a = f.call(1, 2);
+let b = f(1, 2);
+let c = f.curry(1);
+let d = c.call(2);
+
+let g = Fn("greet");
+let e = g.call("world");
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    assert_eq!(decl_ty(&hir, "f"), "|x: ?, y: ?| -> float");
+    assert_eq!(decl_ty(&hir, "a"), "float");
+    assert_eq!(decl_ty(&hir, "b"), "float");
+    assert_eq!(decl_ty(&hir, "c"), "|y: ?| -> float");
+    assert_eq!(decl_ty(&hir, "d"), "float");
+    assert_eq!(decl_ty(&hir, "g"), "fn (name: ?) -> String");
+    assert_eq!(decl_ty(&hir, "e"), "String");
+
+    let errors = hir
+        .errors()
+        .into_iter()
+        .filter(|error| matches!(error.kind, ErrorKind::FnPointerArityMismatch { .. }))
+        .count();
+    assert_eq!(errors, 0);
+}
+
+#[test]
+fn test_closure_call_arity() {
+    let src = r#"
+let f = |x| x;
+
+f.call();
+f(1, 2);
+f.curry(1, 2);
+f.curry(1);
+f.call(1);
+
+fn apply(callback, value) {
+    callback.call(value, value)
+}
+"#;
+
+    let def_src = r#"
+module;
+
+fn apply(callback: |int| -> int, value: int) -> int;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///root.d.rhai".parse().unwrap(),
+        &Parser::new(def_src).parse_def().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let mut mismatches = hir
+        .errors()
+        .into_iter()
+        .filter_map(|error| match error.kind {
+            ErrorKind::FnPointerArityMismatch {
+                expected, found, ..
+            } => Some((expected, found)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    mismatches.sort_unstable();
+
+    assert_eq!(mismatches, [(1, 0), (1, 2), (1, 2), (1, 2)]);
+}
+
+#[test]
+fn test_fn_pointer_overloads() {
+    let src = r#"
+fn add(a, b) { a + b }
+fn add(a) { a }
+fn single(a) { a }
+
+Fn("add").call(1, 2);
+Fn("add").call(1);
+Fn("single").call(1, 2);
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let mismatches = hir
+        .errors()
+        .into_iter()
+        .filter_map(|error| match error.kind {
+            ErrorKind::FnPointerArityMismatch {
+                expected, found, ..
+            } => Some((expected, found)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(mismatches, [(1, 2)]);
+}
+
+#[test]
+fn test_fn_name_references() {
+    let src = r#"
+fn handler(x) {}
+fn handler(x, y) {}
+
+let f = Fn("handler");
+call_fn("handler", 1);
+other_fn("handler");
+let g = Fn("missing");
+"#;
+
+    let mut hir = Hir::new();
+    hir.set_fn_name_functions([String::from("call_fn")]);
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    // Strings refer to every overload.
+    let handlers = hir
+        .symbols()
+        .filter_map(|(_, data)| data.kind.as_fn().filter(|f| f.name == "handler"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(handlers.len(), 2);
+
+    for handler in handlers {
+        let mut references = handler
+            .references
+            .iter()
+            .map(|&reference| {
+                assert_eq!(hir[reference].kind.as_lit().unwrap().targets.len(), 2);
+                let range = hir[reference].selection_range().unwrap();
+                &src[range]
+            })
+            .collect::<Vec<_>>();
+        references.sort_unstable();
+
+        assert_eq!(references, ["handler", "handler"]);
+    }
+
+    let unresolved = hir
+        .errors()
+        .into_iter()
+        .filter_map(|error| match error.kind {
+            ErrorKind::UnresolvedReference {
+                reference_symbol, ..
+            } if hir[reference_symbol].kind.is_lit() => {
+                Some(&src[hir[reference_symbol].selection_range().unwrap()])
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(unresolved, ["missing"]);
+}
@@ -1,4 +1,4 @@
-use rhai_hir::Hir;
+use rhai_hir::{error::ErrorKind, Hir};
 use rhai_rowan::parser::Parser;
 
 #[test]
@@ -82,7 +82,13 @@ fn print();
 
     hir.resolve_all();
 
-    assert!(hir.errors().is_empty());
+    // `print` is resolved to the declaration, but the script does not define it.
+    let errors = hir.errors();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0].kind,
+        ErrorKind::MissingFnDefinition { name, .. } if name == "print"
+    ));
 }
 
 #[test]
@@ -110,5 +116,277 @@ fn print();
 
     hir.resolve_all();
 
-    assert!(hir.errors().is_empty());
+    // `print` is resolved to the declaration, but the script does not define it.
+    let errors = hir.errors();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0].kind,
+        ErrorKind::MissingFnDefinition { name, .. } if name == "print"
+    ));
+}
+
+#[test]
+fn test_deprecated_definitions() {
+    let root_src = r#"
+old_print("hello");
+new_print("hello");
+let x = OLD_LIMIT;
+
+/// @deprecated
+fn old_script_fn() {}
+
+old_script_fn();
+"#;
+
+    let global_src = r#"
+module static;
+
+/// Print a value.
+///
+/// # Deprecated
+///
+/// Use `new_print` instead.
+fn old_print(value: ?);
+
+fn new_print(value: ?);
+
+/// @deprecated
+const OLD_LIMIT: int;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///static.d.rhai".parse().unwrap(),
+        &Parser::new(global_src).parse_def().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let mut deprecated = hir
+        .errors()
+        .into_iter()
+        .map(|error| match error.kind {
+            ErrorKind::Deprecated { name, reason, .. } => (name, reason),
+            kind => panic!("unexpected error: {kind}"),
+        })
+        .collect::<Vec<_>>();
+    deprecated.sort();
+
+    assert_eq!(
+        deprecated,
+        [
+            ("OLD_LIMIT".into(), String::new()),
+            ("old_print".into(), "Use `new_print` instead.".into()),
+            ("old_script_fn".into(), String::new()),
+        ]
+    );
+}
+
+#[test]
+fn test_generic_definitions() {
+    let root_src = r#"
+let a = first([1, 2]);
+let b = map(["a"], |s| 2.0);
+"#;
+
+    let static_src = r#"
+module static;
+
+fn first<T>(arr: [T]) -> T;
+
+fn map<T, U>(arr: [T], f: |T| -> U) -> [U];
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///static.d.rhai".parse().unwrap(),
+        &Parser::new(static_src).parse_def().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    assert!(hir.errors().is_empty(), "{:?}", hir.errors());
+
+    let type_of = |name: &str| {
+        hir.symbols()
+            .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == name))
+            .map(|(_, data)| data.ty.fmt(&hir).to_string())
+            .unwrap()
+    };
+
+    let first = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_fn().is_some_and(|f| f.name == "first"))
+        .map(|(_, data)| data.ty.fmt(&hir).to_string())
+        .unwrap();
+
+    assert_eq!(first, "fn <T>(arr: [T]) -> T");
+    assert_eq!(type_of("a"), "int");
+    assert_eq!(type_of("b"), "[float]");
+}
+
+#[test]
+fn test_script_definition_annotates_functions() {
+    let root_src = r#"
+/// Adds two numbers.
+fn add(a, b) {
+    a + b
+}
+
+fn greet(name) {
+    "hello " + name
+}
+
+let sum = add(1, 2);
+"#;
+
+    let def_src = r#"
+module;
+
+/// This is not used, the script has its own docs.
+fn add(a: int, b: int) -> int;
+
+/// Greets someone.
+fn greet(name: String) -> String;
+
+fn host_fn(x: int);
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///root.d.rhai".parse().unwrap(),
+        &Parser::new(def_src).parse_def().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let errors = hir.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].to_string(),
+        "function `host_fn` with 1 parameter(s) is declared, but the script does not define it"
+    );
+
+    let script_fn = |name: &str| {
+        hir.symbols()
+            .find(|(_, data)| {
+                data.kind
+                    .as_fn()
+                    .is_some_and(|f| !f.is_def && f.name == name)
+            })
+            .map(|(_, data)| data)
+            .unwrap()
+    };
+
+    assert_eq!(
+        script_fn("add").ty.fmt(&hir).to_string(),
+        "fn (a: int, b: int) -> int"
+    );
+    assert_eq!(script_fn("add").docs(), Some("Adds two numbers."));
+
+    assert_eq!(
+        script_fn("greet").ty.fmt(&hir).to_string(),
+        "fn (name: String) -> String"
+    );
+    assert_eq!(script_fn("greet").docs(), Some("Greets someone."));
+
+    let sum = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == "sum"))
+        .map(|(_, data)| data.ty.fmt(&hir).to_string())
+        .unwrap();
+    assert_eq!(sum, "int");
+}
+
+#[test]
+fn test_script_definition_arity_mismatch() {
+    let root_src = r#"
+fn render(x) {}
+"#;
+
+    let def_src = r#"
+module;
+
+fn render(x: int, y: int);
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///root.d.rhai".parse().unwrap(),
+        &Parser::new(def_src).parse_def().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let errors = hir.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].to_string(),
+        "function `render` is declared with 2 parameter(s), but defined with 1 parameter(s)"
+    );
+
+    // The script function is not annotated by a declaration
+    // with a different arity.
+    let render = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_fn().is_some_and(|f| !f.is_def))
+        .map(|(_, data)| data.kind.as_fn().unwrap())
+        .unwrap();
+    assert!(render.definition.is_none());
+}
+
+#[test]
+fn test_script_definition_undeclared_functions() {
+    let root_src = r#"
+fn declared() {}
+fn undeclared(a) {}
+private fn helper() {}
+"#;
+
+    let def_src = r#"
+module;
+
+fn declared();
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///root.d.rhai".parse().unwrap(),
+        &Parser::new(def_src).parse_def().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    // Private functions are not visible outside of the script.
+    let errors = hir.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].to_string(),
+        "function `undeclared` with 1 parameter(s) is not declared in the definition file of the script"
+    );
 }
@@ -0,0 +1,122 @@
+use rhai_hir::{
+    docs::{links, Docs},
+    symbol::SymbolKind,
+    Hir,
+};
+use rhai_rowan::parser::Parser;
+
+#[test]
+fn test_parse_doc_sections() {
+    let docs = Docs::parse(
+        r#"Add two numbers.
+
+Works with any integers.
+
+# Parameters
+
+* `a` - the first number
+* `b`: the second
+  number
+
+# Returns
+
+The sum.
+
+# Example
+
+```rhai
+add(1, 2);
+```
+
+@deprecated use [`sum`] instead
+@since 1.2.0"#,
+    );
+
+    assert_eq!(docs.summary, "Add two numbers.");
+    assert_eq!(docs.description, "Works with any integers.");
+    assert_eq!(docs.params.len(), 2);
+    assert_eq!(docs.params[0].name, "a");
+    assert_eq!(docs.params[0].docs, "the first number");
+    assert_eq!(docs.params[1].name, "b");
+    assert_eq!(docs.params[1].docs, "the second\nnumber");
+    assert_eq!(docs.returns.as_deref(), Some("The sum."));
+    assert_eq!(docs.examples, ["```rhai\nadd(1, 2);\n```"]);
+    assert_eq!(docs.deprecated.as_deref(), Some("use [`sum`] instead"));
+    assert_eq!(docs.since.as_deref(), Some("1.2.0"));
+}
+
+#[test]
+fn test_parse_doc_tags() {
+    let docs = Docs::parse(
+        r#"Greet someone.
+@param name who to greet
+@returns the greeting
+@deprecated"#,
+    );
+
+    assert_eq!(docs.summary, "Greet someone.");
+    assert_eq!(docs.param("name").unwrap().docs, "who to greet");
+    assert_eq!(docs.returns.as_deref(), Some("the greeting"));
+    assert_eq!(docs.deprecated.as_deref(), Some(""));
+    assert!(docs.since.is_none());
+}
+
+#[test]
+fn test_doc_links() {
+    let text = "See [`foo`], [`m::bar()`] and [`baz`](https://example.com).";
+
+    let found = links(text).collect::<Vec<_>>();
+
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].target, "foo");
+    assert_eq!(&text[found[0].range.clone()], "[`foo`]");
+    assert_eq!(found[1].target, "m::bar");
+}
+
+#[test]
+fn test_resolve_doc_links() {
+    let root_src = r#"
+import "./module.rhai" as m;
+
+/// Calls [`helper`] and [`m::exported`].
+fn documented() {}
+
+fn helper() {}
+"#;
+
+    let module_src = r#"
+fn exported() {}
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///module.rhai".parse().unwrap(),
+        &Parser::new(module_src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let find_fn = |name: &str| {
+        hir.symbols()
+            .find(|(_, data)| matches!(&data.kind, SymbolKind::Fn(f) if f.name == name))
+            .unwrap()
+            .0
+    };
+
+    let documented = find_fn("documented");
+
+    assert_eq!(
+        hir.resolve_doc_link(documented, "helper"),
+        Some(find_fn("helper"))
+    );
+    assert_eq!(
+        hir.resolve_doc_link(documented, "m::exported"),
+        Some(find_fn("exported"))
+    );
+    assert_eq!(hir.resolve_doc_link(documented, "missing"), None);
+}
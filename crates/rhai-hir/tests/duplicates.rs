@@ -0,0 +1,155 @@
+use rhai_hir::{error::ErrorKind, Hir};
+use rhai_rowan::parser::Parser;
+
+fn hir_for(src: &str) -> Hir {
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+    hir
+}
+
+#[test]
+fn test_duplicate_switch_arms() {
+    let src = r#"
+const ONE = 1;
+let x = 2;
+
+switch x {
+    1 => "a",
+    ONE => "b",
+    2 if x > 0 => "c",
+    2 => "d",
+    _ => "e",
+    3 => "f",
+}
+"#;
+
+    let hir = hir_for(src);
+
+    let errors = hir.errors();
+
+    assert_eq!(errors.len(), 2);
+
+    let duplicate = errors
+        .iter()
+        .find_map(|e| match &e.kind {
+            ErrorKind::DuplicateSwitchArm {
+                duplicate_symbol,
+                existing_symbol,
+            } => Some((*duplicate_symbol, *existing_symbol)),
+            _ => None,
+        })
+        .unwrap();
+
+    assert_eq!(hir[duplicate.0].kind.as_reference().unwrap().name, "ONE");
+    assert!(hir[duplicate.1].kind.is_lit());
+
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e.kind, ErrorKind::UnreachableSwitchArm { .. })));
+}
+
+#[test]
+fn test_duplicate_object_keys() {
+    let src = r#"
+let obj = #{ a: 1, b: 2, a: 3 };
+"#;
+
+    let hir = hir_for(src);
+
+    let errors = hir.errors();
+
+    assert_eq!(errors.len(), 1);
+
+    match &errors[0].kind {
+        ErrorKind::DuplicateObjectKey {
+            name,
+            duplicate_range,
+            existing_range,
+            ..
+        } => {
+            assert_eq!(name, "a");
+            assert!(existing_range.start() < duplicate_range.start());
+        }
+        _ => panic!("expected duplicate key error"),
+    }
+}
+
+#[test]
+fn test_duplicate_fn_definitions() {
+    let src = r#"
+fn update(x) {}
+fn update(x, y) {}
+fn update(a) {}
+private fn update() {}
+"#;
+
+    let hir = hir_for(src);
+
+    let errors = hir.errors();
+
+    assert_eq!(errors.len(), 1);
+
+    match &errors[0].kind {
+        ErrorKind::DuplicateFnDefinition {
+            duplicate_symbol,
+            existing_symbol,
+            name,
+            arity,
+        } => {
+            assert_eq!(name, "update");
+            assert_eq!(*arity, 1);
+            assert!(
+                hir[*existing_symbol].text_range().unwrap().start()
+                    < hir[*duplicate_symbol].text_range().unwrap().start()
+            );
+        }
+        _ => panic!("expected duplicate function error"),
+    }
+}
+
+#[test]
+fn test_conflicting_fn_definitions() {
+    let script_src = r#"
+fn update(x) {}
+fn render(x) {}
+"#;
+
+    let def_src = r#"
+module;
+
+fn update(x: int);
+fn update(x: float);
+fn render(x: int, y: int);
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(script_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///root.d.rhai".parse().unwrap(),
+        &Parser::new(def_src).parse_def().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let errors = hir.errors();
+
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().any(|error| matches!(
+        &error.kind,
+        ErrorKind::ConflictingFnDefinition { name, arity: 1, .. } if name == "update"
+    )));
+    assert!(errors.iter().any(|error| matches!(
+        &error.kind,
+        ErrorKind::FnArityMismatch { name, expected: 2, found: 1, .. } if name == "render"
+    )));
+}
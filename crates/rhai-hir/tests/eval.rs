@@ -0,0 +1,100 @@
+use rhai_hir::{error::ErrorKind, Hir, Symbol};
+use rhai_rowan::parser::Parser;
+
+fn hir_for(src: &str) -> Hir {
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+    hir
+}
+
+fn decl(hir: &Hir, name: &str) -> Symbol {
+    hir.symbols()
+        .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == name))
+        .map(|(sym, _)| sym)
+        .unwrap()
+}
+
+#[test]
+fn test_eval_constants() {
+    let src = r#"
+const TIMEOUT = 60 * 1000;
+const NAME = "rhai";
+const GREETING = `hello ${NAME}!` + '?';
+const LIMIT = TIMEOUT > 1000 && NAME == "rhai";
+const VALUES = [1, 2.5, TIMEOUT / 2];
+const MAP = #{ a: 1, b: NAME };
+const FIELD = MAP.b;
+const LAST = VALUES[-1];
+const FALLBACK = NAME ?? "default";
+const PLAIN = `plain`;
+
+let variable = 2;
+const NOT_CONSTANT = variable + 1;
+"#;
+
+    let hir = hir_for(src);
+
+    let value_of = |name: &str| hir.eval_symbol(decl(&hir, name)).to_string();
+
+    assert_eq!(value_of("TIMEOUT"), "60000");
+    assert_eq!(value_of("GREETING"), r#""hello rhai!?""#);
+    assert_eq!(value_of("LIMIT"), "true");
+    assert_eq!(value_of("VALUES"), "[1, 2.5, 30000]");
+    assert_eq!(value_of("MAP"), r#"#{ a: 1, b: "rhai" }"#);
+    assert_eq!(value_of("FIELD"), r#""rhai""#);
+    assert_eq!(value_of("LAST"), "30000");
+    assert_eq!(value_of("FALLBACK"), r#""rhai""#);
+    assert_eq!(value_of("PLAIN"), r#""plain""#);
+    assert!(hir.eval_symbol(decl(&hir, "NOT_CONSTANT")).is_unknown());
+}
+
+#[test]
+fn test_eval_errors() {
+    let src = r#"
+const ZERO = 1 - 1;
+let a = 10 / ZERO;
+let b = 10.0 / ZERO;
+
+if ZERO > 0 {
+} else if true {
+}
+"#;
+
+    let hir = hir_for(src);
+
+    let errors = hir.errors();
+
+    assert_eq!(errors.len(), 2);
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e.kind, ErrorKind::DivisionByZero { .. })));
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e.kind, ErrorKind::ConstantCondition { value: false, .. })));
+}
+
+#[test]
+fn test_float_division_by_zero() {
+    let src = r#"
+let a = 1.0 / 0;
+let b = 1.0 % 0;
+let c = unknown / 0;
+let d = 1 / 0;
+"#;
+
+    let hir = hir_for(src);
+
+    let division_errors = hir
+        .errors()
+        .into_iter()
+        .filter(|e| matches!(e.kind, ErrorKind::DivisionByZero { .. }))
+        .count();
+
+    assert_eq!(division_errors, 1);
+}
@@ -1,4 +1,4 @@
-use rhai_hir::Hir;
+use rhai_hir::{error::ErrorKind, module::FileModuleResolver, Hir};
 use rhai_rowan::parser::Parser;
 
 #[test]
@@ -83,3 +83,261 @@ import "./foo.rhai" as foo;
 
     assert_eq!(hir.missing_modules().len(), 1);
 }
+
+#[test]
+fn test_missing_module_candidates() {
+    let root_src = r#"
+import "util" as util;
+import "./local" as local;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.set_import_resolver(
+        FileModuleResolver::new().with_search_url("test:///lib".parse().unwrap()),
+    );
+
+    hir.add_source(
+        &"test:///scripts/root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    assert_eq!(
+        hir.missing_module_candidates(),
+        vec![
+            vec![
+                "test:///scripts/util.rhai".parse().unwrap(),
+                "test:///lib/util.rhai".parse().unwrap()
+            ],
+            vec!["test:///scripts/local.rhai".parse().unwrap()],
+        ]
+    );
+}
+
+#[test]
+fn test_module_graph() {
+    let root_src = r#"
+import "./foo.rhai" as foo;
+"#;
+
+    let foo_src = r#"
+import "./bar.rhai" as bar;
+"#;
+
+    let bar_src = r#"
+export const baz = 1;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///foo.rhai".parse().unwrap(),
+        &Parser::new(foo_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///bar.rhai".parse().unwrap(),
+        &Parser::new(bar_src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let module = |url: &str| hir.module_by_url(&url.parse().unwrap()).unwrap();
+
+    let graph = hir.module_graph();
+
+    assert_eq!(graph.edges.len(), 2);
+    assert_eq!(graph.imports_of(module("test:///foo.rhai")).count(), 1);
+    assert!(graph.cycles().is_empty());
+
+    let dependents = graph.transitive_dependents(module("test:///bar.rhai"));
+
+    assert_eq!(dependents.len(), 2);
+    assert!(dependents.contains(&module("test:///root.rhai")));
+    assert!(dependents.contains(&module("test:///foo.rhai")));
+    assert!(graph
+        .transitive_dependents(module("test:///root.rhai"))
+        .is_empty());
+
+    let dependencies = graph.transitive_dependencies(module("test:///root.rhai"));
+
+    assert_eq!(dependencies.len(), 2);
+    assert!(dependencies.contains(&module("test:///foo.rhai")));
+    assert!(dependencies.contains(&module("test:///bar.rhai")));
+}
+
+#[test]
+fn test_cyclic_imports() {
+    let root_src = r#"
+import "./foo.rhai" as foo;
+"#;
+
+    let foo_src = r#"
+import "./bar.rhai" as bar;
+"#;
+
+    let bar_src = r#"
+import "./foo.rhai" as foo;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///foo.rhai".parse().unwrap(),
+        &Parser::new(foo_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///bar.rhai".parse().unwrap(),
+        &Parser::new(bar_src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let errors = hir.errors();
+
+    assert_eq!(errors.len(), 1);
+
+    match &errors[0].kind {
+        ErrorKind::CyclicImport { cycle, .. } => assert_eq!(cycle.len(), 1),
+        _ => panic!("expected cyclic import error"),
+    }
+
+    let root = hir
+        .source_by_url(&"test:///root.rhai".parse().unwrap())
+        .unwrap();
+    assert!(hir.errors_for_source(root).is_empty());
+
+    let foo = hir
+        .module_by_url(&"test:///foo.rhai".parse().unwrap())
+        .unwrap();
+    assert_eq!(hir.module_graph().transitive_dependents(foo).len(), 3);
+}
+
+#[test]
+fn test_private_module_items() {
+    let root_src = r#"
+import "./module.rhai" as m;
+
+m::visible();
+m::x;
+m::y;
+m::helper();
+m::hidden;
+"#;
+
+    let module_src = r#"
+fn visible() {}
+private fn helper() {}
+
+export const x = 1;
+let hidden = 2;
+let y = 3;
+export y;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///module.rhai".parse().unwrap(),
+        &Parser::new(module_src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let mut private_names = hir
+        .errors()
+        .into_iter()
+        .map(|error| match error.kind {
+            ErrorKind::PrivateModuleItem { name, .. } => name,
+            kind => panic!("unexpected error: {kind}"),
+        })
+        .collect::<Vec<_>>();
+    private_names.sort();
+
+    assert_eq!(private_names, ["helper", "hidden"]);
+}
+
+#[test]
+fn test_file_module_resolver() {
+    let root_src = r#"
+import "std/math" as math;
+import "util" as util;
+import "api" as api;
+import "./local" as local;
+
+math::PI;
+util::helper;
+api::call;
+local::x;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.set_import_resolver(
+        FileModuleResolver::new()
+            .with_base_url("test:///scripts/modules".parse().unwrap())
+            .with_search_url("test:///lib".parse().unwrap())
+            .with_alias("std", "test:///vendor/std".parse().unwrap())
+            .with_alias("api", "rhai-static://api".parse().unwrap()),
+    );
+
+    hir.add_source(
+        &"test:///api.d.rhai".parse().unwrap(),
+        &Parser::new("module api;\nfn call();")
+            .parse_def()
+            .into_syntax(),
+    );
+    hir.add_source(
+        &"test:///scripts/root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///vendor/std/math.rhai".parse().unwrap(),
+        &Parser::new("export const PI = 3.14;")
+            .parse_script()
+            .into_syntax(),
+    );
+    hir.add_source(
+        &"test:///lib/util.rhai".parse().unwrap(),
+        &Parser::new("export const helper = 1;")
+            .parse_script()
+            .into_syntax(),
+    );
+    // Relative paths are resolved from the base path as well.
+    hir.add_source(
+        &"test:///scripts/modules/local.rhai".parse().unwrap(),
+        &Parser::new("export const x = 1;")
+            .parse_script()
+            .into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    assert!(hir.errors().is_empty(), "{:#?}", hir.errors());
+    assert_eq!(hir.missing_modules().len(), 0);
+
+    // Modules in the base path take precedence over search paths.
+    hir.add_source(
+        &"test:///scripts/modules/util.rhai".parse().unwrap(),
+        &Parser::new("export const other = 1;")
+            .parse_script()
+            .into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    assert_eq!(hir.errors().len(), 1);
+}
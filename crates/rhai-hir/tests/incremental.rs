@@ -0,0 +1,189 @@
+use rhai_hir::Hir;
+use rhai_rowan::parser::Parser;
+
+fn add_script(hir: &mut Hir, url: &str, src: &str) {
+    hir.add_source(
+        &url.parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+}
+
+fn resolve_changed(hir: &mut Hir, url: &str) {
+    let source = hir.source_by_url(&url.parse().unwrap()).unwrap();
+    hir.resolve_sources([source]);
+}
+
+#[test]
+fn test_resolve_changed_sources() {
+    let root_src = r#"
+import "./foo.rhai" as foo;
+
+let a = foo::x;
+let b = local;
+"#;
+
+    let other_src = r#"
+let c = 1;
+let d = c + missing;
+"#;
+
+    let mut hir = Hir::new();
+
+    add_script(&mut hir, "test:///root.rhai", root_src);
+    add_script(&mut hir, "test:///foo.rhai", "export const y = 1;");
+    add_script(&mut hir, "test:///other.rhai", other_src);
+    hir.resolve_all();
+
+    // `foo::x`, `local` and `missing`.
+    assert_eq!(hir.errors().len(), 3);
+
+    add_script(&mut hir, "test:///foo.rhai", "export const x = 1;");
+    resolve_changed(&mut hir, "test:///foo.rhai");
+
+    assert_eq!(hir.errors().len(), 2);
+
+    add_script(
+        &mut hir,
+        "test:///root.rhai",
+        r#"
+import "./foo.rhai" as foo;
+
+let local = 2;
+let a = foo::x;
+let b = local;
+"#,
+    );
+    resolve_changed(&mut hir, "test:///root.rhai");
+
+    assert_eq!(hir.errors().len(), 1);
+
+    // References in unaffected sources are kept.
+    let c = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == "c"))
+        .map(|(_, data)| data)
+        .unwrap();
+
+    assert_eq!(c.kind.as_decl().unwrap().references.len(), 1);
+
+    let x = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == "x"))
+        .map(|(_, data)| data)
+        .unwrap();
+
+    assert_eq!(x.kind.as_decl().unwrap().references.len(), 1);
+}
+
+#[test]
+fn test_resolve_changed_sources_matches_full_resolution() {
+    let foo_src = r#"
+import "./bar.rhai" as bar;
+
+export const value = bar::value;
+"#;
+
+    let mut hir = Hir::new();
+
+    add_script(
+        &mut hir,
+        "test:///root.rhai",
+        "import \"./foo.rhai\" as foo;\nlet a = foo::value;",
+    );
+    add_script(&mut hir, "test:///foo.rhai", foo_src);
+    add_script(&mut hir, "test:///bar.rhai", "export const other = 1;");
+    hir.resolve_all();
+
+    add_script(&mut hir, "test:///bar.rhai", "export const value = 1;");
+    resolve_changed(&mut hir, "test:///bar.rhai");
+
+    let mut full = hir.clone();
+    full.resolve_all();
+
+    assert_eq!(hir.errors().len(), full.errors().len());
+    assert!(hir.errors().is_empty());
+
+    let type_of_a = |hir: &Hir| {
+        hir.symbols()
+            .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == "a"))
+            .map(|(_, data)| data.ty.fmt(hir).to_string())
+            .unwrap()
+    };
+
+    assert_eq!(type_of_a(&hir), "int");
+    assert_eq!(type_of_a(&hir), type_of_a(&full));
+}
+
+#[test]
+fn test_resolve_changed_definitions() {
+    let global_url = "test:///global.d.rhai";
+
+    let add_global = |hir: &mut Hir, name: &str| {
+        hir.add_source(
+            &global_url.parse().unwrap(),
+            &Parser::new(&format!("module global;\n\nconst {name}: int;"))
+                .parse_def()
+                .into_syntax(),
+        );
+    };
+
+    let mut hir = Hir::new();
+
+    add_script(&mut hir, "test:///root.rhai", "let a = global::VALUE;\na");
+    add_global(&mut hir, "VALUE");
+    hir.resolve_all();
+
+    assert!(hir.errors().is_empty());
+
+    // The root script does not import the definition,
+    // but it still has to be resolved again.
+    add_global(&mut hir, "OTHER");
+    resolve_changed(&mut hir, global_url);
+
+    assert_eq!(hir.errors().len(), 1);
+
+    add_global(&mut hir, "VALUE");
+    resolve_changed(&mut hir, global_url);
+
+    assert!(hir.errors().is_empty());
+}
+
+#[test]
+fn test_resolve_added_and_removed_modules() {
+    let root_src = r#"
+import "./foo.rhai" as foo;
+
+let a = foo::x;
+"#;
+
+    let mut hir = Hir::new();
+
+    add_script(&mut hir, "test:///root.rhai", root_src);
+    hir.resolve_all();
+
+    let missing_errors = hir.errors().len();
+    assert_ne!(missing_errors, 0);
+
+    // The import is resolved once the module exists.
+    add_script(&mut hir, "test:///foo.rhai", "export const x = 1;");
+    resolve_changed(&mut hir, "test:///foo.rhai");
+
+    assert!(hir.errors().is_empty());
+
+    let foo = hir
+        .module_by_url(&"test:///foo.rhai".parse().unwrap())
+        .unwrap();
+    let root = hir
+        .module_by_url(&"test:///root.rhai".parse().unwrap())
+        .unwrap();
+    assert!(hir.dependent_modules(foo).contains(&root));
+
+    // Removed modules are no longer referenced.
+    let foo_source = hir
+        .source_by_url(&"test:///foo.rhai".parse().unwrap())
+        .unwrap();
+    hir.remove_source(foo_source);
+    hir.resolve_sources([]);
+
+    assert_eq!(hir.errors().len(), missing_errors);
+}
@@ -0,0 +1,119 @@
+use rhai_hir::{error::ErrorKind, metadata::Metadata, symbol::SymbolKind, Hir};
+use rhai_rowan::parser::Parser;
+
+const METADATA: &str = r#"{
+  "modules": {
+    "api": {
+      "doc": "The host API.",
+      "functions": [
+        {
+          "baseHash": 1,
+          "fullHash": 2,
+          "namespace": "internal",
+          "access": "public",
+          "name": "fetch",
+          "type": "native",
+          "numParams": 1,
+          "params": [{ "name": "url", "type": "&str" }],
+          "returnType": "RhaiResultOf<ImmutableString>",
+          "signature": "fetch(url: &str) -> RhaiResultOf<ImmutableString>",
+          "docComments": ["/// Fetch a resource."]
+        }
+      ]
+    }
+  },
+  "functions": [
+    {
+      "baseHash": 3,
+      "fullHash": 4,
+      "namespace": "global",
+      "access": "public",
+      "name": "add",
+      "type": "native",
+      "numParams": 2,
+      "params": [{ "name": "a", "type": "i64" }, { "name": "b", "type": "i64" }],
+      "returnType": "i64",
+      "signature": "add(a: i64, b: i64) -> i64",
+      "docComments": ["/// Add two numbers."]
+    },
+    {
+      "baseHash": 5,
+      "fullHash": 6,
+      "namespace": "global",
+      "access": "public",
+      "name": "get$size",
+      "type": "native",
+      "numParams": 1,
+      "params": [{ "name": "this", "type": "&mut Array" }],
+      "returnType": "i64",
+      "signature": "get$size(this: &mut Array) -> i64"
+    },
+    {
+      "baseHash": 7,
+      "fullHash": 8,
+      "namespace": "global",
+      "access": "public",
+      "name": "+",
+      "type": "native",
+      "numParams": 2,
+      "params": [{ "type": "i64" }, { "type": "i64" }],
+      "returnType": "i64",
+      "signature": "+(_: i64, _: i64) -> i64"
+    }
+  ]
+}"#;
+
+#[test]
+fn test_metadata() {
+    let src = r#"
+let x = add(1, 2);
+let y = api::fetch("https://example.com");
+"#;
+
+    let metadata: Metadata = serde_json::from_str(METADATA).unwrap();
+    let metadata_url = "test:///metadata.json".parse().unwrap();
+
+    let mut hir = Hir::new();
+
+    hir.add_metadata(&metadata_url, &metadata);
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    assert!(hir.errors().is_empty(), "{:?}", hir.errors());
+
+    let (add, add_data) = hir
+        .symbols()
+        .find(|(_, data)| matches!(&data.kind, SymbolKind::Fn(f) if f.name == "add"))
+        .unwrap();
+
+    assert_eq!(add_data.docs(), Some("Add two numbers."));
+    assert_eq!(
+        add_data.ty.fmt(&hir).to_string(),
+        "fn (a: int, b: int) -> int"
+    );
+    assert!(hir.module_by_symbol(add) == Some(hir.static_module()));
+
+    let fetch = hir
+        .symbols()
+        .find_map(|(_, data)| data.kind.as_fn().filter(|f| f.name == "fetch"))
+        .unwrap();
+    assert_eq!(fetch.ret_ty.fmt(&hir).to_string(), "String");
+
+    assert!(hir
+        .symbols()
+        .any(|(_, data)| matches!(&data.kind, SymbolKind::Fn(f) if f.name == "size" && f.getter)));
+
+    // Loading the metadata again replaces the previous functions.
+    hir.add_metadata(&metadata_url, &Metadata::default());
+    hir.resolve_all();
+
+    assert!(!hir.errors().is_empty());
+    assert!(hir
+        .errors()
+        .iter()
+        .all(|err| matches!(err.kind, ErrorKind::UnresolvedReference { .. })));
+}
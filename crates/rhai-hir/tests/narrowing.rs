@@ -0,0 +1,195 @@
+use rhai_hir::{Hir, Symbol};
+use rhai_rowan::{parser::Parser, TextSize};
+
+fn hir_of(src: &str) -> Hir {
+    let defs = r#"
+module static;
+
+fn type_of(value: ?) -> String;
+fn is_def_var(name: String) -> bool;
+fn value() -> int | String | ();
+fn maybe_int() -> int | ();
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///static.d.rhai".parse().unwrap(),
+        &Parser::new(defs).parse_def().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    hir
+}
+
+/// The reference before the `$` marker.
+fn reference_at(src: &str) -> (Hir, Symbol) {
+    let offset = src.find('$').unwrap() - 1;
+    let src = src.replace('$', "");
+    let hir = hir_of(&src);
+
+    let source = hir
+        .source_of(&"test:///root.rhai".parse().unwrap())
+        .unwrap();
+    let symbol: Symbol = hir
+        .symbol_selection_at(source, TextSize::from(offset as u32), true)
+        .or_else(|| hir.symbol_at(source, TextSize::from(offset as u32), true))
+        .unwrap();
+
+    (hir, symbol)
+}
+
+/// The type of the reference before the `$` marker.
+fn type_at(src: &str) -> String {
+    let (hir, symbol) = reference_at(src);
+    hir[symbol].ty.fmt(&hir).to_string()
+}
+
+/// Whether the reference before the `$` marker is narrowed.
+fn is_narrowed_at(src: &str) -> bool {
+    let (hir, symbol) = reference_at(src);
+    hir[symbol].kind.as_reference().unwrap().narrowed
+}
+
+#[test]
+fn test_narrow_type_of() {
+    let decl = "let x = value();\n";
+
+    assert_eq!(
+        type_at(&format!(r#"{decl}if type_of(x) == "string" {{ x$ }}"#)),
+        "String"
+    );
+    assert_eq!(
+        type_at(&format!(
+            r#"{decl}if type_of(x) == "string" {{ x }} else {{ x$ }}"#
+        )),
+        "int | ()"
+    );
+    assert_eq!(
+        type_at(&format!(r#"{decl}if "i64" == type_of(x) {{ x$ }}"#)),
+        "int"
+    );
+    assert_eq!(
+        type_at(&format!(r#"{decl}if !(type_of(x) != "()") {{ x$ }}"#)),
+        "()"
+    );
+    assert_eq!(type_at(&format!(r#"{decl}x$"#)), "int | String | ()");
+}
+
+#[test]
+fn test_narrow_unit() {
+    let decl = "let x = maybe_int();\n";
+
+    assert_eq!(type_at(&format!("{decl}if x != () {{ x$ }}")), "int");
+    assert_eq!(type_at(&format!("{decl}if x == () {{ x$ }}")), "()");
+    assert_eq!(
+        type_at(&format!("{decl}if x == () {{ return; }} else {{ x$ }}")),
+        "int"
+    );
+    assert_eq!(type_at(&format!("{decl}if x != () && x$ > 1 {{}}")), "int");
+}
+
+#[test]
+fn test_narrow_literals() {
+    let decl = "let x = value();\n";
+
+    assert_eq!(type_at(&format!("{decl}if x == 2 {{ x$ }}")), "int");
+    // Other values of the same type are still possible.
+    assert_eq!(
+        type_at(&format!("{decl}if x != 2 {{ x$ }}")),
+        "int | String | ()"
+    );
+
+    let decl = "let x = foo();\n";
+
+    // Unknown types are narrowed to the type of the literal.
+    assert_eq!(type_at(&format!(r#"{decl}if x == "a" {{ x$ }}"#)), "String");
+}
+
+#[test]
+fn test_narrowed_references() {
+    let decl = "let x = value();\n";
+
+    assert!(is_narrowed_at(&format!("{decl}if x == 2 {{ x$ }}")));
+    assert!(!is_narrowed_at(&format!("{decl}if x != 2 {{ x$ }}")));
+    assert!(!is_narrowed_at(&format!("{decl}x$")));
+}
+
+#[test]
+fn test_narrow_switch() {
+    let src = r#"
+let x = value();
+switch type_of(x) {
+    "string" => x$,
+    _ => x,
+}
+"#;
+    assert_eq!(type_at(src), "String");
+
+    let src = r#"
+let x = value();
+switch type_of(x) {
+    "string" => x,
+    "()" => x,
+    _ => { x$ }
+}
+"#;
+    assert_eq!(type_at(src), "int");
+}
+
+#[test]
+fn test_no_narrowing_after_assignments() {
+    let decl = "let x = value();\n";
+
+    assert_eq!(
+        type_at(&format!(
+            r#"{decl}if type_of(x) == "int" {{ x = "s"; x$ }}"#
+        )),
+        "int | String | ()"
+    );
+    assert_eq!(
+        type_at(&format!(r#"{decl}if x == () {{ x = "s"; x$ }}"#)),
+        "int | String | ()"
+    );
+    assert_eq!(
+        type_at(&format!("{decl}if x != () && x != 2 {{ x += 1; x$ }}")),
+        "int | String | ()"
+    );
+    // Assignments after the reference do not matter.
+    assert_eq!(
+        type_at(&format!(r#"{decl}if x == () {{ x$; x = "s"; }}"#)),
+        "()"
+    );
+}
+
+#[test]
+fn test_no_narrowing_in_closures() {
+    let src = r#"
+let x = maybe_int();
+if x != () {
+    let f = || x$;
+}
+"#;
+    assert_eq!(type_at(src), "int | ()");
+}
+
+#[test]
+fn test_is_def_var() {
+    let hir = hir_of(
+        r#"
+if is_def_var("y") {
+    print(y);
+}
+
+y;
+"#,
+    );
+
+    // Only the unguarded `y` and `print` are unresolved.
+    assert_eq!(hir.errors().len(), 2, "{:?}", hir.errors());
+}
@@ -0,0 +1,368 @@
+use rhai_hir::{error::ErrorKind, Hir, Symbol};
+use rhai_rowan::parser::Parser;
+
+fn hir_of(src: &str) -> Hir {
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    hir
+}
+
+fn type_of_decl(hir: &Hir, name: &str) -> String {
+    hir.symbols()
+        .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == name))
+        .map(|(_, data)| data.ty.fmt(hir).to_string())
+        .unwrap()
+}
+
+#[test]
+fn test_object_shape_from_literal() {
+    let hir = hir_of(
+        r#"
+let cfg = #{ host: "x", port: 80 };
+let port = cfg.port;
+"#,
+    );
+
+    assert_eq!(type_of_decl(&hir, "cfg"), "#{host: String, port: int}");
+    assert_eq!(type_of_decl(&hir, "port"), "int");
+}
+
+#[test]
+fn test_object_shape_with_duplicate_keys() {
+    let src = r#"
+let cfg = #{ port: "80", host: "x", port: 80, port: 8080 };
+let port = cfg.port;
+"#;
+    let hir = hir_of(src);
+
+    // Rhai rejects the object, the first value is used for its shape.
+    assert_eq!(type_of_decl(&hir, "cfg"), "#{port: String, host: String}");
+    assert_eq!(type_of_decl(&hir, "port"), "String");
+
+    let first = src.find("port").unwrap();
+    let mut duplicates = hir
+        .errors()
+        .into_iter()
+        .filter_map(|error| match error.kind {
+            ErrorKind::DuplicateObjectKey {
+                duplicate_range,
+                existing_range,
+                ..
+            } => Some((
+                usize::from(duplicate_range.start()),
+                usize::from(existing_range.start()),
+            )),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    duplicates.sort_unstable();
+
+    assert_eq!(
+        duplicates,
+        [
+            (src.find("port: 80,").unwrap(), first),
+            (src.find("port: 8080").unwrap(), first),
+        ]
+    );
+}
+
+#[test]
+fn test_object_shape_from_assignments() {
+    let hir = hir_of(
+        r#"
+let cfg = #{ host: "x", port: 80 };
+cfg.tls = true;
+cfg.port = 8080;
+let tls = cfg.tls;
+"#,
+    );
+
+    assert_eq!(
+        type_of_decl(&hir, "cfg"),
+        "#{host: String, port: int, tls: bool}"
+    );
+    assert_eq!(type_of_decl(&hir, "tls"), "bool");
+}
+
+#[test]
+fn test_object_shape_from_return_values() {
+    let hir = hir_of(
+        r#"
+fn make(secure) {
+    if secure {
+        return #{ host: "x", tls: true };
+    }
+
+    #{ host: "y" }
+}
+
+fn first() {
+    let a = 1;
+    #{ a: a }
+}
+
+let cfg = first();
+"#,
+    );
+
+    assert_eq!(type_of_decl(&hir, "cfg"), "#{a: int}");
+
+    let make = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_fn().is_some_and(|f| f.name == "make"))
+        .map(|(_, data)| data.ty.fmt(&hir).to_string())
+        .unwrap();
+
+    assert_eq!(
+        make,
+        "fn (secure: ?) -> #{host: String} | #{host: String, tls: bool}"
+    );
+}
+
+#[test]
+fn test_absent_fields() {
+    let hir = hir_of(
+        r#"
+let cfg = #{ host: "x" };
+cfg.port = 80;
+
+cfg.host;
+cfg.port;
+cfg.tls;
+cfg.len();
+
+let dynamic = #{};
+dynamic.anything;
+"#,
+    );
+
+    let absent = hir
+        .errors()
+        .into_iter()
+        .filter_map(|err| match err.kind {
+            ErrorKind::AbsentField { name, .. } => Some(name),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(absent, ["tls"]);
+}
+
+#[test]
+fn test_absent_fields_set_by_script_methods() {
+    let hir = hir_of(
+        r#"
+fn set_tls() {
+    this.tls = true;
+}
+
+let cfg = #{ host: "x" };
+cfg.set_tls();
+cfg.tls;
+
+let other = #{ host: "x" };
+other.len();
+other.tls;
+"#,
+    );
+
+    let absent = hir
+        .errors()
+        .into_iter()
+        .filter_map(|err| match err.kind {
+            ErrorKind::AbsentField { name, .. } => Some(name),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(absent, ["tls"]);
+}
+
+#[test]
+fn test_absent_fields_of_dynamic_maps() {
+    let hir = hir_of(
+        r#"
+let indexed = #{ a: 1 };
+indexed["b"] = 2;
+indexed.b;
+indexed.c;
+
+let keyed = #{ a: 1 };
+keyed[key] = 2;
+keyed.b;
+
+let extended = #{ a: 1 };
+extended += #{ b: 2 };
+extended.b;
+
+let mixed = #{ a: 1 };
+mixed.mixin(#{ b: 2 });
+mixed.b;
+
+let reassigned = #{ a: 1 };
+reassigned = #{ b: 2 };
+reassigned.b;
+
+let aliased = #{ a: 1 };
+let alias = aliased;
+alias.b = 2;
+aliased.b;
+
+let passed = #{ a: 1 };
+fill(passed);
+passed.b;
+
+let fixed = #{ a: 1 };
+fixed["a"];
+fixed.b;
+"#,
+    );
+
+    let mut absent = hir
+        .errors()
+        .into_iter()
+        .filter_map(|err| match err.kind {
+            ErrorKind::AbsentField { name, .. } => Some(name),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    absent.sort();
+
+    assert_eq!(absent, ["b", "c"]);
+    assert_eq!(type_of_decl(&hir, "indexed"), "#{a: int, b: int}");
+}
+
+fn field_symbol(hir: &Hir, name: &str) -> Symbol {
+    hir.symbols()
+        .find(|(_, data)| data.kind.as_field().is_some_and(|f| f.name == name))
+        .map(|(symbol, _)| symbol)
+        .unwrap()
+}
+
+fn reference_texts<'s>(hir: &Hir, src: &'s str, field: Symbol) -> Vec<&'s str> {
+    let mut ranges = hir[field]
+        .kind
+        .as_field()
+        .unwrap()
+        .references
+        .iter()
+        .map(|&r| hir[r].selection_or_text_range().unwrap())
+        .collect::<Vec<_>>();
+
+    ranges.sort_by_key(|range| range.start());
+
+    ranges.into_iter().map(|range| &src[range]).collect()
+}
+
+#[test]
+fn test_field_references_in_literals() {
+    let src = r#"
+let cfg = #{ retry_count: 3, "max delay": 10 };
+cfg.retry_count = cfg.retry_count + 1;
+let delay = cfg.max_delay;
+"#;
+    let hir = hir_of(src);
+
+    let retry_count = field_symbol(&hir, "retry_count");
+    assert_eq!(
+        reference_texts(&hir, src, retry_count),
+        ["retry_count", "retry_count"]
+    );
+
+    // The selection of string literal names excludes the quotes.
+    let max_delay = field_symbol(&hir, "max delay");
+    assert_eq!(
+        &src[hir[max_delay].selection_or_text_range().unwrap()],
+        "max delay"
+    );
+    assert!(reference_texts(&hir, src, max_delay).is_empty());
+}
+
+#[test]
+fn test_field_references_in_definitions() {
+    let mut hir = Hir::new();
+
+    let def_src = r#"module static;
+let config: #{ retry_count: int, "max delay": float };
+"#;
+    let script_src = r#"
+let a = config.retry_count;
+let b = config.retry_count;
+"#;
+
+    hir.add_source(
+        &"test:///static.d.rhai".parse().unwrap(),
+        &Parser::new(def_src).parse_def().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(script_src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    let retry_count = field_symbol(&hir, "retry_count");
+    assert_eq!(
+        &def_src[hir[retry_count].selection_or_text_range().unwrap()],
+        "retry_count"
+    );
+    assert_eq!(
+        reference_texts(&hir, script_src, retry_count),
+        ["retry_count", "retry_count"]
+    );
+    assert_eq!(type_of_decl(&hir, "a"), "int");
+
+    let max_delay = field_symbol(&hir, "max delay");
+    assert_eq!(
+        &def_src[hir[max_delay].selection_or_text_range().unwrap()],
+        "max delay"
+    );
+}
+
+#[test]
+fn test_field_docs_in_definitions() {
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///static.d.rhai".parse().unwrap(),
+        &Parser::new(
+            r#"module static;
+let config: #{
+    /// How often to retry.
+    retry_count: int,
+    timeout: float,
+};
+"#,
+        )
+        .parse_def()
+        .into_syntax(),
+    );
+    hir.resolve_all();
+
+    let config = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == "config"))
+        .map(|(_, data)| data.ty)
+        .unwrap();
+
+    let object = hir.object_of(config).unwrap();
+    assert_eq!(
+        object.docs.get("retry_count").map(String::as_str),
+        Some("How often to retry.")
+    );
+    assert!(!object.docs.contains_key("timeout"));
+
+    let export = hir.export();
+    let field = export
+        .symbols
+        .iter()
+        .find(|s| s.kind == "Field" && s.name.as_deref() == Some("retry_count"))
+        .unwrap();
+    assert_eq!(field.docs.as_deref(), Some("How often to retry."));
+}
@@ -0,0 +1,195 @@
+use rhai_hir::{error::ErrorKind, Hir};
+use rhai_rowan::parser::Parser;
+
+const STATIC_SRC: &str = r#"
+module static;
+
+/// Appends a number to a string.
+op +(String, int) -> String;
+
+op +(int, int) -> int;
+
+/// @deprecated
+op -(String, String) -> String;
+
+op -(int, int) -> int;
+"#;
+
+fn hir_with_operators(src: &str) -> Hir {
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///static.d.rhai".parse().unwrap(),
+        &Parser::new(STATIC_SRC).parse_def().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+    hir
+}
+
+fn decl_ty(hir: &Hir, name: &str) -> String {
+    hir.symbols()
+        .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == name))
+        .map(|(_, data)| data.ty.fmt(hir).to_string())
+        .unwrap()
+}
+
+#[test]
+fn test_operator_overloads() {
+    let hir = hir_with_operators(
+        r#"
+let a = "value: " + 2;
+let b = 1 + 2;
+"#,
+    );
+
+    assert!(hir.errors().is_empty());
+
+    assert_eq!(decl_ty(&hir, "a"), "String");
+    assert_eq!(decl_ty(&hir, "b"), "int");
+
+    let overload_docs = hir
+        .symbols()
+        .filter_map(|(_, data)| data.kind.as_binary())
+        .map(|binary| {
+            hir[binary.operator.unwrap()]
+                .kind
+                .as_op()
+                .unwrap()
+                .docs
+                .clone()
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(overload_docs.len(), 2);
+    assert!(overload_docs.contains(&"Appends a number to a string.".to_string()));
+    assert!(overload_docs.contains(&String::new()));
+}
+
+#[test]
+fn test_no_matching_operator() {
+    let hir = hir_with_operators(
+        r#"
+let a = "value: " + true;
+let b = unknown_value + true;
+"#,
+    );
+
+    let errors = hir
+        .errors()
+        .into_iter()
+        .filter(|error| matches!(error.kind, ErrorKind::NoMatchingOperator { .. }))
+        .collect::<Vec<_>>();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].to_string(), "no operator `+` for (String, bool)");
+
+    assert_eq!(decl_ty(&hir, "a"), "?");
+}
+
+#[test]
+fn test_deprecated_operator_overloads() {
+    let hir = hir_with_operators(
+        r#"
+let a = "a" - "b";
+let b = 2 - 1;
+"#,
+    );
+
+    let errors = hir.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0].kind,
+        ErrorKind::Deprecated { name, .. } if name == "-"
+    ));
+}
+
+#[test]
+fn test_undeclared_operators_are_not_deprecated() {
+    let hir = hir_with_operators(
+        r#"
+let a = 2 * 3;
+"#,
+    );
+
+    let binary = hir
+        .symbols()
+        .find_map(|(_, data)| data.kind.as_binary())
+        .unwrap();
+
+    assert!(hir.binary_operator_deprecation(binary).is_none());
+}
+
+#[test]
+fn test_custom_operators_with_builtin_operands() {
+    let static_src = r#"
+module static;
+
+const ORIGIN: Vec2;
+
+op +(Vec2, Vec2) -> Vec2;
+"#;
+
+    let src = r#"
+let a = 1 + 2;
+let b = "a" + "b";
+let c = ORIGIN + ORIGIN;
+let d = ORIGIN + 1;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///static.d.rhai".parse().unwrap(),
+        &Parser::new(static_src).parse_def().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    let errors = hir.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].to_string(), "no operator `+` for (Vec2, int)");
+
+    assert_eq!(decl_ty(&hir, "c"), "Vec2");
+}
+
+#[test]
+fn test_custom_operators_next_to_builtin_operators() {
+    let static_src = r#"
+module static;
+
+const UP: Vec3;
+
+op *(float, Vec3) -> Vec3;
+"#;
+
+    let src = r#"
+let a = 2.0 * 3.0;
+let b = 2.0 * 3;
+let c = 2.0 * UP;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///static.d.rhai".parse().unwrap(),
+        &Parser::new(static_src).parse_def().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    assert!(hir.errors().is_empty());
+    assert_eq!(decl_ty(&hir, "c"), "Vec3");
+}
@@ -0,0 +1,129 @@
+use rhai_hir::Hir;
+use rhai_rowan::{parser::Parser, TextRange, TextSize};
+
+const SRC: &str = r#"
+import "./module.rhai" as m;
+
+/// Docs.
+fn add(a, b) {
+    let sum = a + b;
+    if sum > 10 {
+        return sum * 2;
+    }
+    sum
+}
+
+let values = [1, 2, add(3, 4)];
+
+for (v, i) in values {
+    let f = |x| x + v + i;
+    print(`value: ${f(i)}`);
+}
+
+let obj = #{ a: 1, b: #{ c: m::x } };
+"#;
+
+/// Check that the found range contains the offset and
+/// that there is no other range nested inside it that also contains the offset.
+///
+/// Adjacent ranges both contain the offset in case of inclusive lookups,
+/// either one of them is accepted.
+fn assert_innermost(
+    found: Option<TextRange>,
+    ranges: impl Iterator<Item = TextRange>,
+    offset: TextSize,
+    inclusive: bool,
+) {
+    let containing = ranges
+        .filter(|range| (inclusive && range.contains_inclusive(offset)) || range.contains(offset))
+        .collect::<Vec<_>>();
+
+    let Some(found) = found else {
+        assert!(containing.is_empty(), "nothing found at {offset:?}");
+        return;
+    };
+
+    assert!(containing.contains(&found), "{found:?} at {offset:?}");
+    assert!(
+        !containing
+            .iter()
+            .any(|&range| range != found && found.contains_range(range)),
+        "{found:?} is not the innermost range at {offset:?}"
+    );
+}
+
+#[test]
+fn test_positional_lookups() {
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(SRC).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    let source = hir
+        .source_by_url(&"test:///root.rhai".parse().unwrap())
+        .unwrap();
+
+    for offset in 0..=u32::try_from(SRC.len()).unwrap() {
+        let offset = TextSize::from(offset);
+
+        for inclusive in [false, true] {
+            assert_innermost(
+                hir.symbol_at(source, offset, inclusive)
+                    .and_then(|s| hir[s].source.text_range),
+                hir.symbols()
+                    .filter(|(_, d)| d.source.is(source))
+                    .filter_map(|(_, d)| d.source.text_range),
+                offset,
+                inclusive,
+            );
+
+            assert_innermost(
+                hir.symbol_selection_at(source, offset, inclusive)
+                    .and_then(|s| hir[s].source.selection_text_range),
+                hir.symbols()
+                    .filter(|(_, d)| d.source.is(source))
+                    .filter_map(|(_, d)| d.source.selection_text_range),
+                offset,
+                inclusive,
+            );
+
+            let scope = hir.scope_at(source, offset, inclusive).unwrap();
+            let module_scope = hir[hir.module_by_source(source).unwrap()].scope;
+
+            if scope != module_scope {
+                assert_innermost(
+                    hir.scope(scope).and_then(|s| s.source.text_range),
+                    hir.scopes()
+                        .filter(|(_, d)| d.source.is(source))
+                        .filter_map(|(_, d)| d.source.text_range),
+                    offset,
+                    inclusive,
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_positional_index_is_updated() {
+    let mut hir = Hir::new();
+    let url = "test:///root.rhai".parse().unwrap();
+
+    hir.add_source(&url, &Parser::new(SRC).parse_script().into_syntax());
+    hir.add_source(
+        &url,
+        &Parser::new("let a = 1;").parse_script().into_syntax(),
+    );
+
+    let source = hir.source_by_url(&url).unwrap();
+
+    let symbol = hir.symbol_at(source, TextSize::from(4), false).unwrap();
+    assert_eq!(hir[symbol].kind.as_decl().unwrap().name, "a");
+    assert!(hir.symbol_at(source, TextSize::from(20), false).is_none());
+
+    hir.remove_source(source);
+    assert!(hir.symbol_at(source, TextSize::from(4), false).is_none());
+}
@@ -0,0 +1,118 @@
+use rhai_hir::Hir;
+use rhai_rowan::{parser::Parser, TextSize};
+
+#[test]
+fn test_serde_roundtrip() {
+    let def_src = r#"
+module static;
+
+/// Adds two numbers.
+fn add(a: int, b: int) -> int;
+
+const LIMIT: int;
+"#;
+
+    let script_src = r#"
+let x = add(1, LIMIT);
+let y = x + missing;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///api.d.rhai".parse().unwrap(),
+        &Parser::new(def_src).parse_def().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(script_src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    let json = serde_json::to_string(&hir).unwrap();
+    let mut restored: Hir = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.symbols().count(), hir.symbols().count());
+    assert_eq!(restored.errors().len(), hir.errors().len());
+
+    // Positional lookups work without rebuilding anything.
+    let source = restored
+        .source_by_url(&"test:///root.rhai".parse().unwrap())
+        .unwrap();
+    let symbol = restored
+        .symbol_selection_at(source, TextSize::from(5), false)
+        .unwrap();
+    assert_eq!(restored[symbol].kind.as_decl().unwrap().name, "x");
+
+    // The restored HIR can be modified further.
+    restored.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new("let y = add(1, 2);")
+            .parse_script()
+            .into_syntax(),
+    );
+    restored.resolve_all();
+
+    assert!(restored.errors().is_empty());
+}
+
+#[test]
+fn test_export() {
+    let src = r#"
+export const ANSWER = 42;
+
+/// Doubles a number.
+fn double(x) {
+    x * 2
+}
+
+let a = double(ANSWER);
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    let export = hir.export();
+
+    assert_eq!(export.version, rhai_hir::export::EXPORT_VERSION);
+    assert!(export
+        .sources
+        .iter()
+        .any(|s| s.url.as_str() == "test:///root.rhai" && s.kind == "script"));
+
+    let answer = export
+        .symbols
+        .iter()
+        .find(|s| s.kind == "Decl" && s.name.as_deref() == Some("ANSWER"))
+        .unwrap();
+
+    assert!(answer.export);
+    assert_eq!(answer.ty, "int");
+    assert!(answer.selection_range.is_some());
+
+    let double = export
+        .symbols
+        .iter()
+        .find(|s| s.kind == "Fn" && s.name.as_deref() == Some("double"))
+        .unwrap();
+
+    assert_eq!(double.docs.as_deref(), Some("Doubles a number."));
+
+    assert!(export
+        .symbols
+        .iter()
+        .any(|s| s.kind == "Ref" && s.target_symbol == Some(double.id)));
+
+    assert!(export
+        .scopes
+        .iter()
+        .any(|scope| scope.parent_symbol == Some(double.id)));
+
+    let json = serde_json::to_value(&export).unwrap();
+    assert!(json["symbols"][0].get("selectionRange").is_some());
+}
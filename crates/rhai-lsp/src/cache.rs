@@ -0,0 +1,68 @@
+//! Caching of the HIR built from definition files.
+//!
+//! Definition files rarely change but can be large,
+//! so the resolved HIR is stored in the cache directory provided by the client
+//! and loaded back on startup if none of the definition files changed.
+
+use lsp_types::Url;
+use rhai_hir::Hir;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Caches written by a different version of the server are ignored.
+///
+/// The prefix must be bumped whenever the serialized HIR changes.
+const CACHE_VERSION: &str = concat!("2-", env!("CARGO_PKG_VERSION"));
+
+/// The HIR of all the definition files in a workspace.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct DefCache {
+    version: String,
+    /// Content hashes of the definition files by their URLs.
+    hashes: BTreeMap<String, u64>,
+    hir: Hir,
+}
+
+impl DefCache {
+    pub(crate) fn new(hashes: BTreeMap<String, u64>, hir: Hir) -> Self {
+        Self {
+            version: CACHE_VERSION.into(),
+            hashes,
+            hir,
+        }
+    }
+
+    /// Return the cached HIR if it was created
+    /// from exactly the given definition files.
+    pub(crate) fn into_hir(self, hashes: &BTreeMap<String, u64>) -> Option<Hir> {
+        if self.version == CACHE_VERSION && self.hashes == *hashes {
+            Some(self.hir)
+        } else {
+            None
+        }
+    }
+}
+
+/// The path of the cache file for the given workspace.
+pub(crate) fn cache_file(cache_path: &Path, workspace_root: &Url) -> PathBuf {
+    cache_path.join(format!(
+        "hir-{:016x}.json",
+        stable_hash(workspace_root.as_str())
+    ))
+}
+
+/// A hash that is stable across runs and Rust releases.
+///
+/// This is 64-bit FNV-1a, since the standard library does not
+/// guarantee that its hashers stay the same.
+pub(crate) fn stable_hash(text: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    text.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
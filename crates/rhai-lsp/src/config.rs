@@ -28,6 +28,8 @@ fn default_configuration_section() -> String {
 #[serde(rename_all = "camelCase")]
 pub struct LspConfig {
     pub syntax: SyntaxConfig,
+    pub diagnostics: DiagnosticsConfig,
+    pub modules: ModulesConfig,
     pub debug: DebugConfig,
 }
 
@@ -55,6 +57,47 @@ impl Default for SyntaxConfig {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsConfig {
+    /// Warn about closures created in loops that capture the loop variable.
+    pub closure_loop_capture: bool,
+    /// Warn about reading properties that do not exist in object maps
+    /// with a known shape.
+    pub absent_fields: bool,
+}
+
+impl Default for DiagnosticsConfig {
+    fn default() -> Self {
+        Self {
+            closure_loop_capture: true,
+            absent_fields: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModulesConfig {
+    /// Load imported modules that are not included in the workspace.
+    pub load_external: bool,
+    /// The maximum number of imports between a workspace
+    /// file and an external module.
+    pub max_external_depth: usize,
+    /// The maximum size of external module files in bytes.
+    pub max_external_size: usize,
+}
+
+impl Default for ModulesConfig {
+    fn default() -> Self {
+        Self {
+            load_external: true,
+            max_external_depth: 8,
+            max_external_size: 1024 * 1024,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DebugConfig {
@@ -1,11 +1,15 @@
-use crate::world::{Document, World};
+use crate::{
+    utils::symbol_location,
+    world::{Document, Workspace, World},
+};
 use lsp_async_stub::{util::LspExt, Context, RequestWriter};
 use lsp_types::{
-    notification, Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location,
-    PublishDiagnosticsParams, Url,
+    notification, Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, DiagnosticTag,
+    Location, PublishDiagnosticsParams, Range, Url,
 };
 use rhai_common::{environment::Environment, util::Normalize};
-use rhai_hir::{error::ErrorKind, Hir};
+use rhai_hir::{error::ErrorKind, symbol::BinaryOpKind, Symbol};
+use rhai_rowan::TextRange;
 
 #[tracing::instrument(skip_all)]
 pub(crate) async fn publish_all_diagnostics<E: Environment>(context: Context<World<E>>) {
@@ -24,6 +28,42 @@ pub(crate) async fn publish_all_diagnostics<E: Environment>(context: Context<Wor
     }
 }
 
+/// Publish diagnostics for all documents that depend on
+/// the given document directly or transitively.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn publish_dependent_diagnostics<E: Environment>(
+    context: Context<World<E>>,
+    document_url: Url,
+) {
+    let workspaces = context.workspaces.read().await;
+    let ws = workspaces.by_document(&document_url);
+
+    let dependent_urls = match ws
+        .hir
+        .source_by_url(&document_url.clone().normalize())
+        .and_then(|source| ws.hir.module_by_source(source))
+    {
+        Some(module) => ws
+            .hir
+            .dependent_modules(module)
+            .into_iter()
+            .flat_map(|m| ws.hir[m].sources.iter().map(|&s| &ws.hir[s].url))
+            .filter_map(|url| ws.document_url(url))
+            .filter(|&url| *url != document_url)
+            .cloned()
+            .collect::<Vec<_>>(),
+        None => return,
+    };
+    drop(workspaces);
+
+    for url in dependent_urls {
+        context
+            .env
+            .clone()
+            .spawn_local(publish_diagnostics(context.clone(), url));
+    }
+}
+
 #[tracing::instrument(skip_all)]
 pub(crate) async fn publish_diagnostics<E: Environment>(
     mut context: Context<World<E>>,
@@ -39,6 +79,11 @@ pub(crate) async fn publish_diagnostics<E: Environment>(
         None => return,
     };
 
+    // External modules are only used for analysis.
+    if doc.is_external {
+        return;
+    }
+
     collect_syntax_errors(doc, &mut diags);
     drop(workspaces);
 
@@ -63,7 +108,7 @@ pub(crate) async fn publish_diagnostics<E: Environment>(
         None => return,
     };
 
-    collect_hir_errors(&document_url.clone().normalize(), doc, &ws.hir, &mut diags);
+    collect_hir_errors(&document_url.clone().normalize(), ws, doc, &mut diags);
     drop(workspaces);
 
     context.clone().env.spawn_local(async move {
@@ -114,99 +159,284 @@ fn collect_syntax_errors(doc: &Document, diags: &mut Vec<Diagnostic>) {
 }
 
 #[tracing::instrument(skip_all)]
-fn collect_hir_errors(uri: &Url, doc: &Document, hir: &Hir, diags: &mut Vec<Diagnostic>) {
+fn collect_hir_errors<E: Environment>(
+    uri: &Url,
+    ws: &Workspace<E>,
+    doc: &Document,
+    diags: &mut Vec<Diagnostic>,
+) {
+    let hir = &ws.hir;
+    let config = &ws.config.diagnostics;
+
+    let text_range = |range: TextRange| doc.mapper.range(range).unwrap_or_default().into_lsp();
+    let symbol_range =
+        |symbol: Symbol| text_range(hir[symbol].selection_or_text_range().unwrap_or_default());
+    let related = |message: &str, range: Range| {
+        Some(Vec::from([DiagnosticRelatedInformation {
+            message: message.into(),
+            location: Location {
+                range,
+                uri: uri.clone(),
+            },
+        }]))
+    };
+
     if let Some(source) = hir.source_by_url(uri) {
         for error in hir.errors_for_source(source) {
+            let message = error.to_string();
+
             match &error.kind {
                 ErrorKind::DuplicateFnParameter {
                     duplicate_symbol,
                     existing_symbol,
                 } => diags.push(Diagnostic {
-                    range: doc
-                        .mapper
-                        .range(
-                            hir[*duplicate_symbol]
-                                .selection_or_text_range()
-                                .unwrap_or_default(),
-                        )
-                        .unwrap_or_default()
-                        .into_lsp(),
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    code: None,
-                    code_description: None,
-                    source: Some("Rhai".into()),
-                    message: error.to_string(),
-                    related_information: Some(Vec::from([DiagnosticRelatedInformation {
-                        message: "parameter with the same name".into(),
-                        location: Location {
-                            range: doc
-                                .mapper
-                                .range(
-                                    hir[*existing_symbol]
-                                        .selection_or_text_range()
-                                        .unwrap_or_default(),
-                                )
-                                .unwrap_or_default()
-                                .into_lsp(),
-                            uri: uri.clone(),
-                        },
-                    }])),
-                    tags: None,
-                    data: None,
+                    related_information: related(
+                        "parameter with the same name",
+                        symbol_range(*existing_symbol),
+                    ),
+                    ..hir_diagnostic(
+                        symbol_range(*duplicate_symbol),
+                        DiagnosticSeverity::ERROR,
+                        message,
+                    )
                 }),
                 ErrorKind::UnresolvedReference {
                     reference_symbol,
                     similar_name: _,
+                } => diags.push(hir_diagnostic(
+                    symbol_range(*reference_symbol),
+                    DiagnosticSeverity::ERROR,
+                    message,
+                )),
+                ErrorKind::PrivateModuleItem {
+                    reference_symbol,
+                    item,
+                    ..
+                } => diags.push(Diagnostic {
+                    related_information: symbol_location(ws, *item).map(|location| {
+                        Vec::from([DiagnosticRelatedInformation {
+                            message: "not exported from the module".into(),
+                            location,
+                        }])
+                    }),
+                    ..hir_diagnostic(
+                        symbol_range(*reference_symbol),
+                        DiagnosticSeverity::ERROR,
+                        message,
+                    )
+                }),
+                ErrorKind::UnresolvedImport { import } => diags.push(hir_diagnostic(
+                    symbol_range(*import),
+                    DiagnosticSeverity::ERROR,
+                    message,
+                )),
+                ErrorKind::DuplicateFnDefinition {
+                    duplicate_symbol,
+                    existing_symbol,
+                    ..
+                } => diags.push(Diagnostic {
+                    related_information: related(
+                        "function first defined here",
+                        symbol_range(*existing_symbol),
+                    ),
+                    ..hir_diagnostic(
+                        symbol_range(*duplicate_symbol),
+                        DiagnosticSeverity::ERROR,
+                        message,
+                    )
+                }),
+                ErrorKind::ConflictingFnDefinition {
+                    function,
+                    definition,
+                    ..
                 } => diags.push(Diagnostic {
-                    range: doc
-                        .mapper
-                        .range(
+                    related_information: symbol_location(ws, *definition).map(|location| {
+                        Vec::from([DiagnosticRelatedInformation {
+                            message: "declared here".into(),
+                            location,
+                        }])
+                    }),
+                    ..hir_diagnostic(symbol_range(*function), DiagnosticSeverity::ERROR, message)
+                }),
+                ErrorKind::FnArityMismatch {
+                    definition,
+                    function,
+                    ..
+                } => diags.push(Diagnostic {
+                    related_information: symbol_location(ws, *function).map(|location| {
+                        Vec::from([DiagnosticRelatedInformation {
+                            message: "defined here".into(),
+                            location,
+                        }])
+                    }),
+                    ..hir_diagnostic(
+                        symbol_range(*definition),
+                        DiagnosticSeverity::ERROR,
+                        message,
+                    )
+                }),
+                ErrorKind::CyclicImport { import, cycle } => diags.push(Diagnostic {
+                    related_information: Some(
+                        cycle
+                            .iter()
+                            .filter_map(|&import| symbol_location(ws, import))
+                            .map(|location| DiagnosticRelatedInformation {
+                                message: "part of the import cycle".into(),
+                                location,
+                            })
+                            .collect(),
+                    ),
+                    ..hir_diagnostic(symbol_range(*import), DiagnosticSeverity::ERROR, message)
+                }),
+                ErrorKind::Deprecated {
+                    reference_symbol,
+                    target,
+                    ..
+                } => diags.push(Diagnostic {
+                    related_information: target.and_then(|target| {
+                        Some(Vec::from([DiagnosticRelatedInformation {
+                            message: "deprecated here".into(),
+                            location: symbol_location(ws, target)?,
+                        }]))
+                    }),
+                    tags: Some(vec![DiagnosticTag::DEPRECATED]),
+                    ..hir_diagnostic(
+                        text_range(
+                            // Highlight only the operator itself if possible.
                             hir[*reference_symbol]
-                                .selection_or_text_range()
+                                .kind
+                                .as_binary()
+                                .and_then(|binary| binary.op.as_ref())
+                                .and_then(BinaryOpKind::as_custom)
+                                .map(|op| op.range)
+                                .or_else(|| hir[*reference_symbol].selection_or_text_range())
                                 .unwrap_or_default(),
-                        )
-                        .unwrap_or_default()
-                        .into_lsp(),
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    code: None,
-                    code_description: None,
-                    source: Some("Rhai".into()),
-                    message: error.to_string(),
-                    related_information: None,
-                    tags: None,
-                    data: None,
+                        ),
+                        DiagnosticSeverity::WARNING,
+                        message,
+                    )
                 }),
-                ErrorKind::UnresolvedImport { import } => diags.push(Diagnostic {
-                    range: doc
-                        .mapper
-                        .range(hir[*import].selection_or_text_range().unwrap_or_default())
-                        .unwrap_or_default()
-                        .into_lsp(),
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    code: None,
-                    code_description: None,
-                    source: Some("Rhai".into()),
-                    message: error.to_string(),
-                    related_information: None,
-                    tags: None,
-                    data: None,
+                ErrorKind::NestedFunction { function } => diags.push(hir_diagnostic(
+                    symbol_range(*function),
+                    DiagnosticSeverity::ERROR,
+                    message,
+                )),
+                // The script still runs if its definitions are incomplete.
+                ErrorKind::MissingFnDefinition {
+                    definition: function,
+                    ..
+                }
+                | ErrorKind::UndeclaredFn { function, .. } => diags.push(hir_diagnostic(
+                    symbol_range(*function),
+                    DiagnosticSeverity::WARNING,
+                    message,
+                )),
+                ErrorKind::DivisionByZero { operation }
+                | ErrorKind::NoMatchingOperator { operation, .. }
+                | ErrorKind::FnPointerArityMismatch {
+                    call: operation, ..
+                } => diags.push(hir_diagnostic(
+                    symbol_range(*operation),
+                    DiagnosticSeverity::ERROR,
+                    message,
+                )),
+                ErrorKind::ConstantCondition { condition, .. } => diags.push(hir_diagnostic(
+                    symbol_range(*condition),
+                    DiagnosticSeverity::WARNING,
+                    message,
+                )),
+                ErrorKind::DuplicateSwitchArm {
+                    duplicate_symbol,
+                    existing_symbol,
+                } => diags.push(Diagnostic {
+                    related_information: related(
+                        "first case with the same value",
+                        symbol_range(*existing_symbol),
+                    ),
+                    ..hir_diagnostic(
+                        symbol_range(*duplicate_symbol),
+                        DiagnosticSeverity::ERROR,
+                        message,
+                    )
                 }),
-                ErrorKind::NestedFunction { function } => diags.push(Diagnostic {
-                    range: doc
-                        .mapper
-                        .range(hir[*function].selection_or_text_range().unwrap_or_default())
-                        .unwrap_or_default()
-                        .into_lsp(),
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    code: None,
-                    code_description: None,
-                    source: Some("Rhai".into()),
-                    message: error.to_string(),
-                    related_information: None,
-                    tags: None,
-                    data: None,
+                ErrorKind::UnreachableSwitchArm {
+                    arm_symbol,
+                    default_symbol,
+                } => diags.push(Diagnostic {
+                    related_information: related("default case", symbol_range(*default_symbol)),
+                    tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+                    ..hir_diagnostic(
+                        symbol_range(*arm_symbol),
+                        DiagnosticSeverity::ERROR,
+                        message,
+                    )
                 }),
+                ErrorKind::DuplicateObjectKey {
+                    duplicate_range,
+                    existing_range,
+                    ..
+                } => diags.push(Diagnostic {
+                    related_information: related(
+                        "property first defined here",
+                        text_range(*existing_range),
+                    ),
+                    ..hir_diagnostic(
+                        text_range(*duplicate_range),
+                        DiagnosticSeverity::ERROR,
+                        message,
+                    )
+                }),
+                ErrorKind::AbsentField {
+                    reference_symbol, ..
+                } => {
+                    if !config.absent_fields {
+                        continue;
+                    }
+
+                    diags.push(hir_diagnostic(
+                        symbol_range(*reference_symbol),
+                        DiagnosticSeverity::WARNING,
+                        message,
+                    ));
+                }
+                ErrorKind::ClosureCapturesLoopVariable {
+                    reference_symbol,
+                    loop_variable,
+                    ..
+                } => {
+                    if !config.closure_loop_capture {
+                        continue;
+                    }
+
+                    diags.push(Diagnostic {
+                        related_information: related(
+                            "loop variable declared here",
+                            symbol_range(*loop_variable),
+                        ),
+                        ..hir_diagnostic(
+                            symbol_range(*reference_symbol),
+                            DiagnosticSeverity::WARNING,
+                            message,
+                        )
+                    });
+                }
             }
         }
     }
 }
+
+/// A diagnostic for an error from the HIR
+/// without related information or tags.
+fn hir_diagnostic(range: Range, severity: DiagnosticSeverity, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        code: None,
+        code_description: None,
+        source: Some("Rhai".into()),
+        message,
+        related_information: None,
+        tags: None,
+        data: None,
+    }
+}
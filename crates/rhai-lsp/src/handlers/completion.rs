@@ -9,15 +9,16 @@ use lsp_async_stub::{
     Context, Params,
 };
 use lsp_types::{
-    Command, CompletionItem, CompletionItemKind, CompletionParams, CompletionResponse,
-    CompletionTextEdit, Documentation, InsertTextFormat, MarkupContent, MarkupKind, TextEdit,
+    Command, CompletionItem, CompletionItemKind, CompletionItemTag, CompletionParams,
+    CompletionResponse, CompletionTextEdit, Documentation, InsertTextFormat, MarkupContent,
+    MarkupKind, TextEdit,
 };
 use rhai_common::{environment::Environment, util::Normalize};
 use rhai_hir::{
     scope::ScopeParent,
     symbol::{ReferenceTarget, SymbolKind, VirtualSymbol},
     ty::Type,
-    Hir, Symbol, TypeKind,
+    Hir, Symbol,
 };
 use rhai_rowan::{query::Query, TextRange};
 
@@ -102,7 +103,7 @@ pub(crate) async fn completion<E: Environment>(
         if idx == 0 {
             return Ok(Some(CompletionResponse::Array(
                 modules
-                    .filter_map(|symbol| reference_completion(&ws.hir, true, symbol))
+                    .filter_map(|symbol| reference_completion(ws, true, symbol))
                     .unique_by(|(symbol, _)| ws.hir.unique_symbol_name(symbol))
                     .map(|(_, c)| c)
                     .collect(),
@@ -142,7 +143,7 @@ pub(crate) async fn completion<E: Environment>(
         Ok(Some(CompletionResponse::Array(
             symbols
                 .into_iter()
-                .filter_map(|symbol| reference_completion(&ws.hir, false, symbol))
+                .filter_map(|symbol| reference_completion(ws, false, symbol))
                 .unique_by(|(symbol, _)| ws.hir.unique_symbol_name(symbol))
                 .map(|(_, c)| c)
                 .collect(),
@@ -159,7 +160,7 @@ pub(crate) async fn completion<E: Environment>(
                         .and_then(|d| d.alias)
                         .or(Some(symbol))
                 })
-                .filter_map(|symbol| reference_completion(&ws.hir, false, symbol))
+                .filter_map(|symbol| reference_completion(ws, false, symbol))
                 .unique_by(|(symbol, _)| ws.hir.unique_symbol_name(symbol))
                 .map(|(_, c)| c)
                 .collect(),
@@ -185,6 +186,7 @@ pub(crate) async fn completion<E: Environment>(
                             value: op.docs.clone(),
                         })),
                         kind: Some(CompletionItemKind::OPERATOR),
+                        tags: deprecated_tags(op.deprecated().is_some()),
                         insert_text: Some(format!("{} ", &op.name)),
                         command: text_edit.is_none().then(trigger_completion),
                         text_edit,
@@ -205,10 +207,10 @@ fn binary_field_access_completion<E: Environment>(
     query: &Query,
 ) -> std::option::Option<lsp_types::CompletionResponse> {
     if let Some(lhs_ty) = b.lhs.map(|lhs| ws.hir[lhs].ty) {
-        let lhs_ty_data = &ws.hir[lhs_ty];
-
-        match &lhs_ty_data.kind {
-            TypeKind::Object(o) => Some(CompletionResponse::Array(
+        // TODO: handle the rest of the types,
+        // functions with getters and known `this` type.
+        ws.hir.object_of(lhs_ty).map(|o| {
+            CompletionResponse::Array(
                 o.fields
                     .iter()
                     .map(|(name, ty)| {
@@ -217,27 +219,25 @@ fn binary_field_access_completion<E: Environment>(
                             &ws.hir,
                             name,
                             *ty,
+                            o.docs.get(name).map(String::as_str),
                             query.ident().map(|t| t.text_range()),
                         )
                     })
                     .collect(),
-            )),
-            _ => {
-                // TODO: handle the rest of the types,
-                // functions with getters and known `this` type.
-                None
-            }
-        }
+            )
+        })
     } else {
         None
     }
 }
 
-fn reference_completion(
-    hir: &Hir,
+fn reference_completion<E: Environment>(
+    ws: &Workspace<E>,
     ident_only: bool,
     symbol: Symbol,
 ) -> Option<(Symbol, CompletionItem)> {
+    let hir = &ws.hir;
+
     match &hir[symbol].kind {
         SymbolKind::Fn(f) => Some((
             symbol,
@@ -246,9 +246,10 @@ fn reference_completion(
                 detail: Some(signature_of(hir, symbol)),
                 documentation: Some(Documentation::MarkupContent(MarkupContent {
                     kind: MarkupKind::Markdown,
-                    value: documentation_for(hir, symbol, false),
+                    value: documentation_for(ws, symbol, false),
                 })),
                 kind: Some(CompletionItemKind::FUNCTION),
+                tags: deprecated_tags(hir[symbol].deprecated().is_some()),
                 insert_text: Some(format!("{}($0)", &f.name)),
                 insert_text_format: Some(InsertTextFormat::SNIPPET),
                 ..CompletionItem::default()
@@ -261,7 +262,7 @@ fn reference_completion(
                 detail: Some(signature_of(hir, symbol)),
                 documentation: Some(Documentation::MarkupContent(MarkupContent {
                     kind: MarkupKind::Markdown,
-                    value: documentation_for(hir, symbol, false),
+                    value: documentation_for(ws, symbol, false),
                 })),
                 kind: Some(if d.is_const {
                     CompletionItemKind::CONSTANT
@@ -270,6 +271,7 @@ fn reference_completion(
                 } else {
                     CompletionItemKind::VARIABLE
                 }),
+                tags: deprecated_tags(hir[symbol].deprecated().is_some()),
                 insert_text: if let Some(ReferenceTarget::Module(m)) = d.target {
                     if ident_only || hir[hir[m].scope].is_empty() {
                         Some(d.name.clone())
@@ -290,7 +292,7 @@ fn reference_completion(
                 detail: Some(signature_of(hir, symbol)),
                 documentation: Some(Documentation::MarkupContent(MarkupContent {
                     kind: MarkupKind::Markdown,
-                    value: documentation_for(hir, symbol, false),
+                    value: documentation_for(ws, symbol, false),
                 })),
                 kind: Some(CompletionItemKind::MODULE),
                 insert_text: if ident_only || hir[hir[m.module].scope].is_empty() {
@@ -310,18 +312,27 @@ fn reference_completion(
     }
 }
 
+fn deprecated_tags(deprecated: bool) -> Option<Vec<CompletionItemTag>> {
+    deprecated.then(|| vec![CompletionItemTag::DEPRECATED])
+}
+
 fn field_completion(
     doc: &Document,
     hir: &Hir,
     name: &str,
     ty: Type,
+    docs: Option<&str>,
     existing_ident: Option<TextRange>,
 ) -> CompletionItem {
     CompletionItem {
         label: name.to_string(),
         detail: Some(format!("{}", ty.fmt(hir))),
-        // TODO: include field docs in types.
-        documentation: None,
+        documentation: docs.map(|docs| {
+            Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: docs.to_string(),
+            })
+        }),
         kind: Some(CompletionItemKind::FIELD),
         insert_text: Some(name.to_string()),
         text_edit: existing_ident.map(|range| {
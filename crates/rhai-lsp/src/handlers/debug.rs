@@ -1,5 +1,5 @@
 use crate::{
-    lsp_ext::request::{HirDumpParams, HirDumpResult},
+    lsp_ext::request::{HirDumpFormat, HirDumpParams, HirDumpResult},
     world::World,
 };
 use lsp_async_stub::{rpc, Context, Params};
@@ -23,10 +23,16 @@ pub(crate) async fn hir_dump<E: Environment>(
     };
 
     Ok(Some(HirDumpResult {
-        hir: if ws.config.debug.hir.full {
-            format!("{:#?}", ws.hir)
-        } else {
-            HirFmt::new(&ws.hir).with_source().to_string()
+        hir: match p.format {
+            HirDumpFormat::Json => serde_json::to_string_pretty(&ws.hir.export())
+                .map_err(|_| rpc::Error::internal_error())?,
+            HirDumpFormat::Text => {
+                if ws.config.debug.hir.full {
+                    format!("{:#?}", ws.hir)
+                } else {
+                    HirFmt::new(&ws.hir).with_source().to_string()
+                }
+            }
         },
     }))
 }
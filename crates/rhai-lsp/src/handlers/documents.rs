@@ -3,9 +3,10 @@ use lsp_types::{
     DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
     DidSaveTextDocumentParams, Url,
 };
-use rhai_common::environment::Environment;
+use rhai_common::{environment::Environment, util::Normalize};
+use rhai_hir::Hir;
 use crate::{
-    diagnostics::{publish_all_diagnostics, publish_diagnostics},
+    diagnostics::{publish_all_diagnostics, publish_dependent_diagnostics, publish_diagnostics},
     world::World,
 };
 
@@ -19,13 +20,22 @@ pub(crate) async fn document_open<E: Environment>(
         Some(p) => p,
     };
 
+    context
+        .workspaces
+        .write()
+        .await
+        .by_document_mut(&p.text_document.uri)
+        .open_documents
+        .insert(p.text_document.uri.clone());
+
     update_document(
         context.clone(),
         p.text_document.uri.clone(),
         &p.text_document.text,
     )
     .await;
-    publish_diagnostics(context.clone(), p.text_document.uri).await;
+    publish_diagnostics(context.clone(), p.text_document.uri.clone()).await;
+    publish_dependent_diagnostics(context.clone(), p.text_document.uri).await;
 
     context
         .clone()
@@ -50,7 +60,8 @@ pub(crate) async fn document_change<E: Environment>(
     };
 
     update_document(context.clone(), p.text_document.uri.clone(), &change.text).await;
-    publish_diagnostics(context.clone(), p.text_document.uri).await;
+    publish_diagnostics(context.clone(), p.text_document.uri.clone()).await;
+    publish_dependent_diagnostics(context.clone(), p.text_document.uri).await;
 
     context
         .clone()
@@ -60,24 +71,71 @@ pub(crate) async fn document_change<E: Environment>(
 
 #[tracing::instrument(skip_all)]
 pub(crate) async fn document_save<E: Environment>(
-    _context: Context<World<E>>,
-    _params: Params<DidSaveTextDocumentParams>,
+    context: Context<World<E>>,
+    params: Params<DidSaveTextDocumentParams>,
 ) {
-    // stub to silence warnings
+    let Some(p) = params.optional() else {
+        return;
+    };
+
+    let mut workspaces = context.workspaces.write().await;
+    let ws = workspaces.by_document_mut(&p.text_document.uri);
+
+    // Imported files might have been created in the meantime.
+    ws.unavailable_modules.clear();
+    ws.load_external_modules().await;
 }
 
 #[tracing::instrument(skip_all)]
 pub(crate) async fn document_close<E: Environment>(
-    _context: Context<World<E>>,
-    _params: Params<DidCloseTextDocumentParams>,
+    context: Context<World<E>>,
+    params: Params<DidCloseTextDocumentParams>,
 ) {
-    // no-op, we track it until it is deleted.
+    let Some(p) = params.optional() else {
+        return;
+    };
+
+    // We keep tracking the document until it is deleted.
+    context
+        .workspaces
+        .write()
+        .await
+        .by_document_mut(&p.text_document.uri)
+        .open_documents
+        .remove(&p.text_document.uri);
 }
 
 #[tracing::instrument(skip_all)]
 pub(crate) async fn update_document<E: Environment>(ctx: Context<World<E>>, uri: Url, text: &str) {
     let mut ws = ctx.workspaces.write().await;
     let ws = ws.by_document_mut(&uri);
+    let source_url = uri.clone().normalize();
+
+    let old_imports = import_paths(&ws.hir, &source_url);
     ws.add_document(uri, text);
-    ws.hir.resolve_all();
+
+    // Only the edited document and its dependents
+    // have to be resolved again.
+    match ws.hir.source_by_url(&source_url) {
+        Some(source) => ws.hir.resolve_sources([source]),
+        None => ws.hir.resolve_all(),
+    }
+
+    // External modules are read from disk,
+    // so they are only loaded again if the imports change.
+    if import_paths(&ws.hir, &source_url) != old_imports {
+        ws.load_external_modules().await;
+    }
+}
+
+fn import_paths(hir: &Hir, source_url: &Url) -> Vec<String> {
+    let Some(source) = hir.source_by_url(source_url) else {
+        return Vec::new();
+    };
+
+    hir.imports_in_source(source)
+        .iter()
+        .filter_map(|&import| hir[import].kind.as_import()?.import_path(hir))
+        .map(ToString::to_string)
+        .collect()
 }
@@ -6,7 +6,7 @@ use lsp_types::{
     request::{GotoDeclarationParams, GotoDeclarationResponse},
     GotoDefinitionParams, GotoDefinitionResponse, LocationLink, Position, Url,
 };
-use rhai_hir::symbol::ReferenceTarget;
+use rhai_hir::symbol::{ReferenceTarget, SymbolKind};
 
 #[tracing::instrument(skip_all)]
 pub(crate) async fn goto_declaration<E: Environment>(
@@ -70,48 +70,50 @@ async fn goto_target<E: Environment>(
         let origin_selection_range = data
             .selection_or_text_range()
             .and_then(|range| doc.mapper.range(range).map(LspExt::into_lsp));
-        match &data.kind {
-            rhai_hir::symbol::SymbolKind::Ref(r) => {
-                if let Some(ReferenceTarget::Symbol(target)) = &r.target {
-                    let target_data = &ws.hir[*target];
-
-                    let target_source = match target_data.source.source {
-                        Some(s) => s,
-                        None => return Ok(None),
-                    };
-
-                    let target_source_data = &ws.hir[target_source];
-
-                    let target_document = match ws.documents.get(&target_source_data.url) {
-                        Some(d) => d,
-                        None => return Ok(None),
-                    };
-
-                    let target_range = match target_data
-                        .text_range()
-                        .and_then(|range| target_document.mapper.range(range).map(LspExt::into_lsp))
-                    {
-                        Some(range) => range,
-                        None => return Ok(None),
-                    };
-
-                    let target_selection_range = target_data
-                        .selection_range()
-                        .and_then(|range| target_document.mapper.range(range).map(LspExt::into_lsp))
-                        .unwrap_or(target_range);
-
-                    return Ok(Some(vec![LocationLink {
-                        origin_selection_range,
-                        target_uri: target_data
-                            .source
-                            .source
-                            .map_or(uri, |s| ws.hir[s].url.clone()),
-                        target_range,
-                        target_selection_range,
-                    }]));
-                }
-            }
-            _ => {}
+        // Strings can also refer to functions, e.g. in `Fn("name")`.
+        let target = match &data.kind {
+            SymbolKind::Ref(r) => r.target,
+            SymbolKind::Lit(l) => l.targets.first().copied().map(ReferenceTarget::Symbol),
+            _ => None,
+        };
+
+        if let Some(ReferenceTarget::Symbol(target)) = &target {
+            let target_data = &ws.hir[*target];
+
+            let target_source = match target_data.source.source {
+                Some(s) => s,
+                None => return Ok(None),
+            };
+
+            let target_source_data = &ws.hir[target_source];
+
+            let target_document = match ws.documents.get(&target_source_data.url) {
+                Some(d) => d,
+                None => return Ok(None),
+            };
+
+            let target_range = match target_data
+                .text_range()
+                .and_then(|range| target_document.mapper.range(range).map(LspExt::into_lsp))
+            {
+                Some(range) => range,
+                None => return Ok(None),
+            };
+
+            let target_selection_range = target_data
+                .selection_range()
+                .and_then(|range| target_document.mapper.range(range).map(LspExt::into_lsp))
+                .unwrap_or(target_range);
+
+            return Ok(Some(vec![LocationLink {
+                origin_selection_range,
+                target_uri: target_data
+                    .source
+                    .source
+                    .map_or(uri, |s| ws.hir[s].url.clone()),
+                target_range,
+                target_selection_range,
+            }]));
         }
     }
 
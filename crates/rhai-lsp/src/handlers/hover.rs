@@ -1,9 +1,16 @@
-use crate::{utils::documentation_for, world::World};
+use crate::{
+    utils::{captures_of, documentation_for, signature_of, RhaiStringExt},
+    world::{Workspace, World},
+};
 use lsp_async_stub::{rpc, util::LspExt, Context, Params};
 use lsp_types::{Hover, HoverContents, HoverParams, MarkupContent, MarkupKind, Range};
 use rhai_common::{environment::Environment, util::Normalize};
-use rhai_hir::{symbol::ReferenceTarget, Hir, Symbol};
-use rhai_rowan::{query::Query, syntax::SyntaxNode, TextSize};
+use rhai_hir::{source::Source, symbol::ReferenceTarget, Symbol};
+use rhai_rowan::{
+    query::Query,
+    syntax::{SyntaxNode, SyntaxToken},
+    TextSize,
+};
 
 pub(crate) async fn hover<E: Environment>(
     context: Context<World<E>>,
@@ -36,6 +43,15 @@ pub(crate) async fn hover<E: Environment>(
 
     let query = Query::at(&syntax, offset);
 
+    if let Some(op) = query.binary_op() {
+        if let Some(hover) = hover_for_operator(ws, source, &op) {
+            return Ok(Some(Hover {
+                range: doc.mapper.range(op.text_range()).map(LspExt::into_lsp),
+                ..hover
+            }));
+        }
+    }
+
     if let Some(ident) = query.binary_op_ident() {
         if let Some(op) = ws.hir.operator_by_name(ident.text()) {
             return Ok(Some(Hover {
@@ -51,6 +67,11 @@ pub(crate) async fn hover<E: Environment>(
     let target_symbol = ws
         .hir
         .symbol_selection_at(source, offset, true)
+        .or_else(|| {
+            ws.hir
+                .symbol_at(source, offset, true)
+                .filter(|&s| ws.hir[s].kind.is_closure())
+        })
         .map(|s| (s, &ws.hir[s]));
 
     if let Some((symbol, data)) = target_symbol {
@@ -59,7 +80,7 @@ pub(crate) async fn hover<E: Environment>(
             .and_then(|range| doc.mapper.range(range).map(LspExt::into_lsp));
 
         return Ok(hover_for_symbol(
-            &ws.hir,
+            ws,
             &doc.parse.clone_syntax(),
             highlight_range,
             symbol,
@@ -69,25 +90,102 @@ pub(crate) async fn hover<E: Environment>(
     Ok(None)
 }
 
-fn hover_for_symbol(
-    hir: &Hir,
+/// The overload of the operator chosen for the types of the operands.
+fn hover_for_operator<E: Environment>(
+    ws: &Workspace<E>,
+    source: Source,
+    op: &SyntaxToken,
+) -> Option<Hover> {
+    let hir = &ws.hir;
+
+    let operation = hir.symbol_at(source, op.text_range().start(), false)?;
+    let overload = hir[operation]
+        .kind
+        .as_binary()?
+        .operator
+        .filter(|&op| hir.symbol(op).is_some())?;
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: documentation_for(ws, overload, true),
+        }),
+        range: None,
+    })
+}
+
+fn hover_for_symbol<E: Environment>(
+    ws: &Workspace<E>,
     root: &SyntaxNode,
     highlight_range: Option<Range>,
     symbol: Symbol,
 ) -> Option<Hover> {
-    match &hir[symbol].kind {
-        rhai_hir::symbol::SymbolKind::Fn(_) | rhai_hir::symbol::SymbolKind::Decl(_) => {
+    let hir = &ws.hir;
+
+    // Targets of references might point to symbols that were removed since.
+    match &hir.symbol(symbol)?.kind {
+        rhai_hir::symbol::SymbolKind::Fn(_)
+        | rhai_hir::symbol::SymbolKind::Decl(_)
+        | rhai_hir::symbol::SymbolKind::Field(_) => Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: documentation_for(ws, symbol, true),
+            }),
+            range: highlight_range,
+        }),
+        rhai_hir::symbol::SymbolKind::Closure(_) => {
+            let mut value = signature_of(hir, symbol).wrap_rhai_markdown();
+
+            if let Some(captures) = captures_of(hir, symbol) {
+                value += "\n";
+                value += &captures;
+            }
+
+            Some(Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value,
+                }),
+                range: highlight_range,
+            })
+        }
+        rhai_hir::symbol::SymbolKind::Ref(r) if r.field_access => {
+            let value = match r.target {
+                // Fields of known objects have their own docs.
+                Some(ReferenceTarget::Symbol(field)) if hir[field].kind.is_field() => {
+                    documentation_for(ws, field, true)
+                }
+                _ => format!("{}: {}", r.name, hir[symbol].ty.fmt(hir)).wrap_rhai_markdown(),
+            };
+
             Some(Hover {
                 contents: HoverContents::Markup(MarkupContent {
                     kind: MarkupKind::Markdown,
-                    value: documentation_for(hir, symbol, true),
+                    value,
                 }),
                 range: highlight_range,
             })
         }
+        // Strings that name functions, e.g. in `Fn("name")`.
+        rhai_hir::symbol::SymbolKind::Lit(l) => {
+            hover_for_symbol(ws, root, highlight_range, *l.targets.first()?)
+        }
         rhai_hir::symbol::SymbolKind::Ref(r) => match &r.target {
             Some(ReferenceTarget::Symbol(target)) => {
-                hover_for_symbol(hir, root, highlight_range, *target)
+                let mut hover = hover_for_symbol(ws, root, highlight_range, *target)?;
+
+                // The type of the variable is narrowed in this branch.
+                if r.narrowed {
+                    if let HoverContents::Markup(content) = &mut hover.contents {
+                        content.value = format!(
+                            "{}\n\nNarrowed to `{}` here.",
+                            content.value,
+                            hir[symbol].ty.fmt(hir)
+                        );
+                    }
+                }
+
+                Some(hover)
             }
             _ => None,
         },
@@ -97,6 +97,7 @@ pub async fn initialized<E: Environment>(
 ) {
     update_configuration(context.clone()).await;
 
+    let init_config = context.init_config.load();
     let mut workspaces = context.workspaces.write().await;
 
     for (ws_url, ws) in workspaces.iter_mut() {
@@ -108,7 +109,7 @@ pub async fn initialized<E: Environment>(
             tracing::error!(%error, "failed to load Rhai config");
         }
 
-        ws.load_all_files().await;
+        ws.load_all_files(init_config.cache_path.as_deref()).await;
     }
 
     drop(workspaces);
@@ -1,9 +1,11 @@
 use crate::world::{Workspace, World};
 use lsp_async_stub::{rpc, util::LspExt, Context, Params};
 use lsp_types::{Location, ReferenceParams};
-use rhai_common::environment::Environment;
-use rhai_hir::Symbol;
-use rhai_rowan::{syntax::SyntaxKind, TextRange, TextSize};
+use rhai_common::{environment::Environment, util::Normalize};
+use rhai_hir::{
+    symbol::{ReferenceTarget, SymbolKind},
+    Symbol,
+};
 
 #[tracing::instrument(skip_all)]
 pub(crate) async fn references<E: Environment>(
@@ -20,35 +22,53 @@ pub(crate) async fn references<E: Environment>(
 
     let doc = ws.document(&uri)?;
 
-    let offset = match doc
+    let Some(offset) = doc
         .mapper
         .offset(lsp_async_stub::util::Position::from_lsp(pos))
-    {
-        Some(p) => p + TextSize::from(1),
-        None => return Ok(None),
+    else {
+        return Ok(None);
     };
 
-    let elem = doc
-        .parse
-        .clone_syntax()
-        .covering_element(TextRange::new(offset, offset));
+    let Some(source) = ws.hir.source_of(&uri.clone().normalize()) else {
+        return Ok(None);
+    };
 
-    if elem.kind() != SyntaxKind::IDENT {
+    let Some(symbol) = ws.hir.symbol_selection_at(source, offset, true) else {
         return Ok(None);
-    }
+    };
 
-    let target_symbol = ws
-        .hir
-        .symbols()
-        .find(|(_, symbol)| symbol.has_selection_range(elem.text_range()));
+    // References are collected for the target of a reference,
+    // strings can also refer to functions, e.g. in `Fn("name")`,
+    // in which case all overloads of the function are included.
+    let target_symbols = match &ws.hir[symbol].kind {
+        SymbolKind::Ref(r) => match r.target {
+            Some(ReferenceTarget::Symbol(target)) => vec![target],
+            _ => vec![symbol],
+        },
+        SymbolKind::Lit(l) if !l.targets.is_empty() => l.targets.clone(),
+        _ => vec![symbol],
+    };
+
+    let mut locations = Vec::new();
 
-    if let Some((sym, _)) = target_symbol {
-        let mut locations = Vec::new();
-        collect_references(ws, sym, p.context.include_declaration, &mut locations);
-        return Ok(Some(locations));
+    for target_symbol in target_symbols {
+        collect_references(
+            ws,
+            target_symbol,
+            p.context.include_declaration,
+            &mut locations,
+        );
+    }
+
+    // Strings are references of every overload.
+    let mut unique_locations = Vec::with_capacity(locations.len());
+    for location in locations {
+        if !unique_locations.contains(&location) {
+            unique_locations.push(location);
+        }
     }
 
-    Ok(None)
+    Ok(Some(unique_locations))
 }
 
 pub(crate) fn collect_references<E: Environment>(
@@ -62,6 +82,7 @@ pub(crate) fn collect_references<E: Environment>(
     let references = match &target_data.kind {
         rhai_hir::symbol::SymbolKind::Fn(f) => &f.references,
         rhai_hir::symbol::SymbolKind::Decl(d) => &d.references,
+        rhai_hir::symbol::SymbolKind::Field(f) => &f.references,
         _ => return,
     };
 
@@ -50,9 +50,14 @@ pub async fn prepare_rename<E: Environment>(
             Some(range) => match &data.kind {
                 symbol::SymbolKind::Fn(_)
                 | symbol::SymbolKind::Decl(_)
+                | symbol::SymbolKind::Field(_)
                 | symbol::SymbolKind::Ref(_) => {
                     Some(PrepareRenameResponse::Range(range.into_lsp()))
                 }
+                // Strings that name functions, e.g. in `Fn("name")`.
+                symbol::SymbolKind::Lit(l) if !l.targets.is_empty() => {
+                    Some(PrepareRenameResponse::Range(range.into_lsp()))
+                }
                 _ => None,
             },
             None => None,
@@ -104,6 +109,8 @@ pub async fn rename<E: Environment>(
                 },
                 None => {}
             }
+        } else if let Some(&target) = data.kind.as_lit().and_then(|l| l.targets.first()) {
+            target_symbol = Some((target, &ws.hir[target]));
         } else {
             target_symbol = Some((sym, data));
         }
@@ -111,10 +118,10 @@ pub async fn rename<E: Environment>(
 
     Ok(
         target_symbol.and_then(|(target_symbol, data)| match &data.kind {
-            symbol::SymbolKind::Fn(target) => Some(WorkspaceEdit {
+            symbol::SymbolKind::Fn(_) => Some(WorkspaceEdit {
                 changes: Some(rename_symbols(
                     &ws.hir,
-                    iter::once(target_symbol).chain(target.references.iter().copied()),
+                    fn_overloads_with_references(&ws.hir, target_symbol).into_iter(),
                     &p.new_name,
                     ws,
                 )),
@@ -129,11 +136,48 @@ pub async fn rename<E: Environment>(
                 )),
                 ..Default::default()
             }),
+            symbol::SymbolKind::Field(target) => Some(WorkspaceEdit {
+                changes: Some(rename_symbols(
+                    &ws.hir,
+                    iter::once(target_symbol).chain(target.references.iter().copied()),
+                    &p.new_name,
+                    ws,
+                )),
+                ..Default::default()
+            }),
             _ => None,
         }),
     )
 }
 
+/// The function and its references, strings that name the function
+/// also name its overloads, so they are included with their references as well.
+fn fn_overloads_with_references(hir: &Hir, function: Symbol) -> Vec<Symbol> {
+    let references = |f: Symbol| {
+        hir.symbol(f)
+            .and_then(|data| data.kind.as_fn())
+            .map(|f| f.references.iter().copied().collect::<Vec<_>>())
+            .unwrap_or_default()
+    };
+
+    let overloads = references(function)
+        .into_iter()
+        .filter_map(|r| hir.symbol(r).and_then(|data| data.kind.as_lit()))
+        .flat_map(|l| l.targets.iter().copied());
+
+    let mut symbols = Vec::new();
+
+    for f in iter::once(function).chain(overloads) {
+        for symbol in iter::once(f).chain(references(f)) {
+            if !symbols.contains(&symbol) {
+                symbols.push(symbol);
+            }
+        }
+    }
+
+    symbols
+}
+
 fn rename_symbols<E: Environment>(
     hir: &Hir,
     symbols: impl Iterator<Item = Symbol>,
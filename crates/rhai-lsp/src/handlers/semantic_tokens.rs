@@ -12,9 +12,10 @@ use rhai_common::{environment::Environment, util::Normalize};
 use rhai_hir::{
     symbol::{BinaryOpKind, ReferenceTarget, SymbolKind},
     ty::Type,
-    Hir, TypeKind,
+    Hir, Symbol, TypeKind,
 };
 use rhai_rowan::TextRange;
+use std::collections::HashSet;
 
 #[tracing::instrument(skip_all)]
 pub(crate) async fn semantic_tokens<E: Environment>(
@@ -32,57 +33,56 @@ pub(crate) async fn semantic_tokens<E: Environment>(
         return Ok(None);
     }
 
-    let source = match ws.hir.source_by_url(&p.text_document.uri.clone().normalize()) {
+    let source = match ws
+        .hir
+        .source_by_url(&p.text_document.uri.clone().normalize())
+    {
         Some(s) => s,
         None => return Ok(None),
     };
 
     let mut token_builder = SemanticTokensBuilder::new(&doc.mapper);
 
+    let captured: HashSet<Symbol> = ws
+        .hir
+        .symbols()
+        .filter(|(_, data)| data.source.is(source))
+        .filter_map(|(_, data)| data.kind.as_closure())
+        .flat_map(|closure| closure.captures.iter().copied())
+        .collect();
+
     token_builder.extend(ws.hir.symbols().filter_map(|(symbol, data)| {
         if !data.source.is(source) {
             return None;
         }
 
         match &data.kind {
-            SymbolKind::Decl(d) => {
-                if let Some(ty) = token_for_ty(&ws.hir, ws.hir[symbol].ty) {
-                    Some((ws.hir[symbol].selection_range()?, ty, vec![]))
-                } else if d.is_const {
-                    Some((
-                        ws.hir[symbol].selection_range()?,
-                        TokenType::Variable,
-                        vec![TokenModifier::ReadOnly],
-                    ))
-                } else {
-                    None
-                }
+            SymbolKind::Decl(_) => {
+                let (ty, modifiers) = token_for_decl(&ws.hir, symbol, &captured)?;
+                Some((ws.hir[symbol].selection_range()?, ty, modifiers))
             }
             SymbolKind::Ref(r) => {
-                if let Some(&target_symbol) = r.target.as_ref().and_then(ReferenceTarget::as_symbol)
-                {
-                    if let Some(ty) = token_for_ty(&ws.hir, ws.hir[target_symbol].ty) {
-                        Some((ws.hir[symbol].selection_range()?, ty, vec![]))
-                    } else if let Some(d) = ws.hir[target_symbol].kind.as_decl() {
-                        if  d.is_const {
-                            Some((
-                                ws.hir[symbol].selection_range()?,
-                                TokenType::Variable,
-                                vec![TokenModifier::ReadOnly],
-                            ))
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
+                let &target_symbol = r.target.as_ref().and_then(ReferenceTarget::as_symbol)?;
+                let (ty, modifiers) = token_for_decl(&ws.hir, target_symbol, &captured)?;
+                Some((ws.hir[symbol].selection_range()?, ty, modifiers))
             }
             SymbolKind::Path(p) => p.segments.last().and_then(|&sym| {
+                let deprecated = ws.hir[sym]
+                    .kind
+                    .as_reference()
+                    .and_then(|r| r.target.as_ref().and_then(ReferenceTarget::as_symbol))
+                    .is_some_and(|&target| ws.hir[target].deprecated().is_some());
+
                 if let Some(ty) = token_for_ty(&ws.hir, ws.hir[sym].ty) {
-                    Some((ws.hir[sym].selection_range()?, ty, vec![]))
+                    Some((
+                        ws.hir[sym].selection_range()?,
+                        ty,
+                        if deprecated {
+                            vec![TokenModifier::Deprecated]
+                        } else {
+                            vec![]
+                        },
+                    ))
                 } else if let Some(&target) = ws.hir[sym]
                     .kind
                     .as_reference()
@@ -93,7 +93,11 @@ pub(crate) async fn semantic_tokens<E: Environment>(
                             Some((
                                 ws.hir[sym].selection_range()?,
                                 TokenType::Variable,
-                                vec![TokenModifier::ReadOnly],
+                                if deprecated {
+                                    vec![TokenModifier::ReadOnly, TokenModifier::Deprecated]
+                                } else {
+                                    vec![TokenModifier::ReadOnly]
+                                },
                             ))
                         } else {
                             None
@@ -105,11 +109,19 @@ pub(crate) async fn semantic_tokens<E: Environment>(
                     None
                 }
             }),
-            SymbolKind::Binary(b) => {
-                b.op.as_ref()
-                    .and_then(BinaryOpKind::as_custom)
-                    .map(|c| (c.range, TokenType::CustomOperator, vec![]))
-            }
+            SymbolKind::Binary(b) => b.op.as_ref().and_then(BinaryOpKind::as_custom).map(|c| {
+                let deprecated = ws.hir.binary_operator_deprecation(b).is_some();
+
+                (
+                    c.range,
+                    TokenType::CustomOperator,
+                    if deprecated {
+                        vec![TokenModifier::Deprecated]
+                    } else {
+                        vec![]
+                    },
+                )
+            }),
             _ => None,
         }
     }));
@@ -120,6 +132,42 @@ pub(crate) async fn semantic_tokens<E: Environment>(
     })))
 }
 
+/// Token for a declaration or a reference to it.
+///
+/// Constants are marked read-only, variables captured by closures are marked as captured,
+/// and items with a deprecation marker in their docs are marked as deprecated.
+fn token_for_decl(
+    hir: &Hir,
+    symbol: Symbol,
+    captured: &HashSet<Symbol>,
+) -> Option<(TokenType, Vec<TokenModifier>)> {
+    let mut modifiers = Vec::new();
+
+    if hir[symbol].deprecated().is_some() {
+        modifiers.push(TokenModifier::Deprecated);
+    }
+
+    if let Some(ty) = token_for_ty(hir, hir[symbol].ty) {
+        return Some((ty, modifiers));
+    }
+
+    let decl = hir[symbol].kind.as_decl()?;
+
+    if decl.is_const {
+        modifiers.push(TokenModifier::ReadOnly);
+    }
+
+    if captured.contains(&symbol) {
+        modifiers.push(TokenModifier::Captured);
+    }
+
+    if modifiers.is_empty() {
+        None
+    } else {
+        Some((TokenType::Variable, modifiers))
+    }
+}
+
 fn token_for_ty(hir: &Hir, ty: Type) -> Option<TokenType> {
     match &hir[ty].kind {
         TypeKind::Module => Some(TokenType::Module),
@@ -146,15 +194,20 @@ impl TokenType {
     ];
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Copy, Clone)]
 #[repr(u32)]
 pub enum TokenModifier {
     ReadOnly,
+    Captured,
+    Deprecated,
 }
 
 impl TokenModifier {
-    pub const MODIFIERS: &'static [SemanticTokenModifier] = &[SemanticTokenModifier::READONLY];
+    pub const MODIFIERS: &'static [SemanticTokenModifier] = &[
+        SemanticTokenModifier::READONLY,
+        SemanticTokenModifier::new("captured"),
+        SemanticTokenModifier::DEPRECATED,
+    ];
 }
 
 struct SemanticTokensBuilder<'b> {
@@ -214,13 +267,9 @@ impl<'b> SemanticTokensBuilder<'b> {
                     .character
                     .saturating_sub(relative.start.character)) as u32,
                 token_type: ty as u32,
-                token_modifiers_bitset: modifiers.iter().enumerate().fold(
-                    0,
-                    |mut total, (i, _)| {
-                        total += 1 << i;
-                        total
-                    },
-                ),
+                token_modifiers_bitset: modifiers
+                    .iter()
+                    .fold(0, |total, &modifier| total | (1 << modifier as u32)),
             });
 
             last_range = Some(range.into_lsp());
@@ -1,6 +1,6 @@
 use lsp_async_stub::{Context, Params};
 use lsp_types::{DidChangeWatchedFilesParams, FileChangeType};
-use rhai_common::environment::Environment;
+use rhai_common::{environment::Environment, util::Normalize};
 use crate::{
     diagnostics::{clear_diagnostics, publish_all_diagnostics},
     world::World,
@@ -22,6 +22,12 @@ pub(crate) async fn watched_file_change<E: Environment>(
 
         let mut workspaces = context.workspaces.write().await;
 
+        // The file might be an import that could not be loaded before.
+        workspaces
+            .by_document_mut(&uri)
+            .unavailable_modules
+            .remove(&uri.clone().normalize());
+
         match change.typ {
             FileChangeType::CREATED | FileChangeType::CHANGED => {
                 let path = match context.env.url_to_file_path(&uri) {
@@ -54,6 +60,9 @@ pub(crate) async fn watched_file_change<E: Environment>(
             FileChangeType::DELETED => {
                 let ws = workspaces.by_document_mut(&uri);
                 ws.remove_document(&uri) ;
+                // Resolves the dependents of the removed document.
+                ws.hir.resolve_sources([]);
+                ws.load_external_modules().await;
                 clear_diagnostics(context.clone(), uri).await;
             }
             _ => {
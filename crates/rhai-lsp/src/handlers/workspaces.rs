@@ -15,6 +15,7 @@ pub async fn workspace_change<E: Environment>(
         Some(p) => p,
     };
 
+    let init_config = context.init_config.load();
     let mut workspaces = context.workspaces.write().await;
 
     for removed in p.event.removed {
@@ -27,7 +28,7 @@ pub async fn workspace_change<E: Environment>(
         if let Err(error) = ws.load_rhai_config().await {
             tracing::error!(%error, "invalid configuration");
         }
-        ws.load_all_files().await;
+        ws.load_all_files(init_config.cache_path.as_deref()).await;
 
         workspaces.entry(added.uri).or_insert(ws);
     }
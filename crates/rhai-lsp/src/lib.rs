@@ -17,6 +17,7 @@ use lsp_async_stub::Server;
 use lsp_types::{notification, request};
 pub use world::{World, WorldState};
 
+pub(crate) mod cache;
 pub(crate) mod config;
 pub(crate) mod diagnostics;
 pub(crate) mod lsp_ext;
@@ -8,13 +8,25 @@ pub mod request {
     #[serde(rename_all = "camelCase")]
     pub struct HirDumpParams {
         pub workspace_uri: Option<Url>,
+        #[serde(default)]
+        pub format: HirDumpFormat,
+    }
+
+    #[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub enum HirDumpFormat {
+        /// The unstable textual representation.
+        #[default]
+        Text,
+        /// The stable and versioned JSON representation.
+        Json,
     }
 
     #[derive(Serialize, Deserialize)]
     #[serde(rename_all = "camelCase")]
     pub struct HirDumpResult {
-        /// The unstable textual representation
-        /// of the HIR.
+        /// The representation of the HIR
+        /// in the requested format.
         pub hir: String,
     }
 
@@ -1,22 +1,38 @@
-use std::{sync::Arc, time::Duration};
+use std::{fmt::Write, sync::Arc, time::Duration};
 
 use arc_swap::ArcSwapOption;
 use futures::{
     future::{AbortHandle, Abortable},
     Future,
 };
-use rhai_hir::{symbol::SymbolKind, Hir, Symbol};
+use lsp_async_stub::util::LspExt;
+use lsp_types::{Location, Url};
+use rhai_hir::{
+    docs::{self, Docs},
+    eval::Value,
+    symbol::SymbolKind,
+    Hir, Symbol,
+};
 
 use rhai_common::environment::Environment;
 
+use crate::world::Workspace;
+
 /// Format signatures and definitions of symbols.
 pub fn signature_of(hir: &Hir, symbol: Symbol) -> String {
     let sym_data = &hir[symbol];
 
     match &sym_data.kind {
         SymbolKind::Decl(decl) => {
+            let value = if decl.is_const {
+                Some(hir.eval_symbol(symbol)).filter(Value::is_known)
+            } else {
+                None
+            };
+
             format!(
-                "{}{}: {}",
+                "{}{}{}: {}{}",
+                visibility_of(hir, symbol),
                 if decl.is_param {
                     ""
                 } else if decl.is_const {
@@ -25,16 +41,55 @@ pub fn signature_of(hir: &Hir, symbol: Symbol) -> String {
                     "let "
                 },
                 decl.name,
-                sym_data.ty.fmt(hir)
+                sym_data.ty.fmt(hir),
+                value.map(|v| format!(" = {v}")).unwrap_or_default()
             )
         }
+        SymbolKind::Fn(_) => {
+            format!("{}{}", visibility_of(hir, symbol), sym_data.ty.fmt(hir))
+        }
+        SymbolKind::Field(field) => format!("{}: {}", field.name, sym_data.ty.fmt(hir)),
+        SymbolKind::Op(op) => format!("op {}{}", op.name, op.signature(hir)),
         _ => {
             format!("{}", sym_data.ty.fmt(hir))
         }
     }
 }
 
-pub fn documentation_for(hir: &Hir, symbol: Symbol, signature: bool) -> String {
+/// The visibility of top-level script items from other modules.
+///
+/// Functions are exported unless they are `private`,
+/// variables are only exported with `export`.
+fn visibility_of(hir: &Hir, symbol: Symbol) -> &'static str {
+    let sym_data = &hir[symbol];
+
+    let is_script = sym_data
+        .source
+        .source
+        .is_some_and(|source| hir[source].kind.is_script());
+
+    let is_top_level = hir
+        .module_by_symbol(symbol)
+        .is_some_and(|m| hir[m].scope == sym_data.parent_scope);
+
+    if !is_script || !is_top_level {
+        return "";
+    }
+
+    match &sym_data.kind {
+        SymbolKind::Fn(_) if !sym_data.export => "private ",
+        SymbolKind::Decl(decl) if sym_data.export && !decl.is_import => "export ",
+        _ => "",
+    }
+}
+
+pub fn documentation_for<E: Environment>(
+    ws: &Workspace<E>,
+    symbol: Symbol,
+    signature: bool,
+) -> String {
+    let hir = &ws.hir;
+
     if let Some(m) = hir.target_module(symbol) {
         return hir[m].docs.clone();
     }
@@ -48,13 +103,24 @@ pub fn documentation_for(hir: &Hir, symbol: Symbol, signature: bool) -> String {
     let sym_data = &hir[symbol];
 
     if let Some(docs) = sym_data.docs() {
+        let captures = sym_data
+            .kind
+            .as_decl()
+            .and_then(|decl| decl.value)
+            .and_then(|value| captures_of(hir, value))
+            .map(|captures| format!("\n{captures}"))
+            .unwrap_or_default();
+
+        let docs = render_docs(&Docs::parse(docs));
+
         return format!(
-            "{sig}{docs}",
+            "{sig}{captures}{docs}",
             sig = sig,
+            captures = captures,
             docs = if docs.is_empty() {
                 String::new()
             } else {
-                format!("\n{}", docs)
+                format!("\n{}", resolve_doc_links(ws, symbol, &docs))
             }
         );
     }
@@ -62,6 +128,131 @@ pub fn documentation_for(hir: &Hir, symbol: Symbol, signature: bool) -> String {
     String::new()
 }
 
+/// Render the recognized sections of the docs as markdown.
+fn render_docs(docs: &Docs) -> String {
+    let mut sections = Vec::new();
+
+    if let Some(reason) = &docs.deprecated {
+        if reason.is_empty() {
+            sections.push("**Deprecated**".to_string());
+        } else {
+            sections.push(format!("**Deprecated**: {reason}"));
+        }
+    }
+
+    sections.extend(
+        [&docs.summary, &docs.description]
+            .into_iter()
+            .filter(|text| !text.is_empty())
+            .cloned(),
+    );
+
+    if !docs.params.is_empty() {
+        let params = docs
+            .params
+            .iter()
+            .map(|param| {
+                if param.docs.is_empty() {
+                    format!("- `{}`", param.name)
+                } else {
+                    format!("- `{}`: {}", param.name, param.docs)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        sections.push(format!("**Parameters**\n\n{params}"));
+    }
+
+    if let Some(returns) = &docs.returns {
+        sections.push(format!("**Returns**: {returns}"));
+    }
+
+    for example in &docs.examples {
+        sections.push(format!("**Example**\n\n{example}"));
+    }
+
+    if let Some(since) = &docs.since {
+        sections.push(format!("*Since: {since}*"));
+    }
+
+    sections.join("\n\n")
+}
+
+/// Turn `` [`name`] `` links into links to the location of the
+/// linked symbols, unresolved links are rendered as code.
+fn resolve_doc_links<E: Environment>(ws: &Workspace<E>, symbol: Symbol, text: &str) -> String {
+    let mut resolved = String::with_capacity(text.len());
+    let mut last_end = 0;
+
+    for link in docs::links(text) {
+        resolved += &text[last_end..link.range.start];
+        last_end = link.range.end;
+
+        match ws
+            .hir
+            .resolve_doc_link(symbol, link.target)
+            .and_then(|target| symbol_link(ws, target))
+        {
+            Some(url) => write!(resolved, "[`{}`]({url})", link.target).unwrap(),
+            None => write!(resolved, "`{}`", link.target).unwrap(),
+        }
+    }
+
+    resolved += &text[last_end..];
+    resolved
+}
+
+/// A link to the location of the symbol that can be opened by clients.
+fn symbol_link<E: Environment>(ws: &Workspace<E>, symbol: Symbol) -> Option<Url> {
+    let location = symbol_location(ws, symbol)?;
+    let mut url = location.uri;
+    url.set_fragment(Some(&format!(
+        "L{},{}",
+        location.range.start.line + 1,
+        location.range.start.character + 1
+    )));
+    Some(url)
+}
+
+/// Location of a symbol in any document of the workspace.
+pub(crate) fn symbol_location<E: Environment>(
+    ws: &Workspace<E>,
+    symbol: Symbol,
+) -> Option<Location> {
+    let symbol_data = &ws.hir[symbol];
+    let url = ws.document_url(&ws.hir[symbol_data.source.source?].url)?;
+    let doc = ws.documents.get(url)?;
+
+    Some(Location {
+        range: doc
+            .mapper
+            .range(symbol_data.selection_or_text_range()?)?
+            .into_lsp(),
+        uri: url.clone(),
+    })
+}
+
+/// List the variables captured by a closure, if any.
+pub fn captures_of(hir: &Hir, symbol: Symbol) -> Option<String> {
+    let closure = hir[symbol].kind.as_closure()?;
+
+    if closure.captures.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "captures: {}",
+        closure
+            .captures
+            .iter()
+            .filter_map(|&sym| hir[sym].name(hir))
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
 pub trait RhaiStringExt {
     fn wrap_rhai_markdown(&self) -> String;
 }
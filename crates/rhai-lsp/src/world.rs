@@ -1,4 +1,5 @@
 use crate::{
+    cache::{self, DefCache},
     config::{InitConfig, LspConfig},
     utils::Debouncer,
     IndexMap,
@@ -9,12 +10,19 @@ use lsp_async_stub::{rpc, util::Mapper};
 use lsp_types::Url;
 use once_cell::sync::Lazy;
 use rhai_common::{config::Config, environment::Environment, util::Normalize};
-use rhai_hir::{ty::Type, Hir};
+use rhai_hir::{
+    builtin::StdPackage, metadata::Metadata, module::FileModuleResolver, ty::Type, Hir, Module,
+};
 use rhai_rowan::{
     parser::{Operator, Parse, Parser},
     util::{is_rhai_def, is_valid_ident},
 };
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::sync::RwLock as AsyncRwLock;
 
 pub static DEFAULT_WORKSPACE_URL: Lazy<Url> = Lazy::new(|| Url::parse("root:///").unwrap());
@@ -124,19 +132,29 @@ pub struct Workspace<E: Environment> {
     /// A set of custom operators from definitions,
     /// along with their lhs and rhs types.
     pub(crate) custom_operators: HashSet<(String, Type, Type, (u8, u8))>,
+    /// Documents that are open in the editor, these are never external.
+    pub(crate) open_documents: HashSet<Url>,
+    /// Candidate URLs of external modules that could not be loaded.
+    pub(crate) unavailable_modules: HashSet<Url>,
 }
 
 impl<E: Environment> Workspace<E> {
     pub(crate) fn new(env: E, root: Url) -> Self {
         tracing::info!(%root, "created workspace");
+
+        let mut hir = Hir::default();
+        hir.set_std_packages(StdPackage::standard());
+
         Self {
             env,
             root,
             rhai_config: Default::default(),
             config: LspConfig::default(),
             documents: Default::default(),
-            hir: Default::default(),
+            hir,
             custom_operators: Default::default(),
+            open_documents: Default::default(),
+            unavailable_modules: Default::default(),
         }
     }
 }
@@ -181,7 +199,13 @@ impl<E: Environment> Workspace<E> {
         self.rhai_config.prepare(&self.env, &root_path)
     }
 
-    pub(crate) async fn load_all_files(&mut self) {
+    /// Load all the files included in the workspace.
+    ///
+    /// If a cache path is given, definitions are loaded from
+    /// and saved to the cache.
+    pub(crate) async fn load_all_files(&mut self, cache_path: Option<&Path>) {
+        self.unavailable_modules.clear();
+
         let includes = self.rhai_config.source.include.as_ref().unwrap();
 
         let mut paths = Vec::new();
@@ -210,6 +234,9 @@ impl<E: Environment> Workspace<E> {
 
         tracing::info!(count = all, excluded, "found files");
 
+        let mut defs = Vec::new();
+        let mut scripts = Vec::new();
+
         for path in paths {
             if self.env.is_dir(&path) {
                 continue;
@@ -234,12 +261,192 @@ impl<E: Environment> Workspace<E> {
                 }
             };
 
-            self.add_document(document_url, &source_text);
+            if is_rhai_def(&source_text) {
+                defs.push((document_url, source_text));
+            } else {
+                scripts.push((document_url, source_text));
+            }
+        }
+
+        // Definitions are added first, they can be loaded from
+        // the cache and custom operators are known before
+        // the scripts are parsed.
+        let cache_file = cache_path.map(|p| cache::cache_file(p, &self.root));
+
+        let std_packages = match StdPackage::select(self.rhai_config.std.packages()) {
+            Ok(packages) => packages,
+            Err(error) => {
+                tracing::error!(%error, "invalid built-in package selection");
+                StdPackage::standard().collect()
+            }
+        };
+
+        let mut metadata = Vec::new();
+
+        for path in self.rhai_config.source.metadata.iter().flatten() {
+            let text = match self.env.read_file(Path::new(path)).await {
+                Ok(bytes) => bytes,
+                Err(error) => {
+                    tracing::error!(%error, %path, "failed to read metadata file");
+                    continue;
+                }
+            };
+
+            let Ok(url) = Url::from_file_path(path) else {
+                tracing::error!(%path, "metadata path is not a valid file URL");
+                continue;
+            };
+
+            match serde_json::from_slice::<Metadata>(&text) {
+                Ok(m) => {
+                    let hash = cache::stable_hash(&String::from_utf8_lossy(&text));
+                    metadata.push((url, m, hash));
+                }
+                Err(error) => tracing::error!(%error, %path, "invalid metadata file"),
+            }
+        }
+
+        let modules = &self.rhai_config.modules;
+        let module_resolver = FileModuleResolver::from_config(
+            modules.base_url(),
+            modules.search_urls(),
+            modules.alias_urls(),
+            modules.extension.as_deref(),
+        );
+        self.hir.set_import_resolver(module_resolver.clone());
+        let fn_name_functions = self
+            .rhai_config
+            .source
+            .fn_name_functions
+            .clone()
+            .unwrap_or_default();
+        self.hir.set_fn_name_functions(fn_name_functions.clone());
+
+        let mut hashes = defs
+            .iter()
+            .map(|(url, text)| (url.to_string(), cache::stable_hash(text)))
+            .chain(
+                metadata
+                    .iter()
+                    .map(|(url, _, hash)| (url.to_string(), *hash)),
+            )
+            .chain(std_packages.iter().map(|package| {
+                (
+                    package.url().to_string(),
+                    cache::stable_hash(package.definitions()),
+                )
+            }))
+            .collect::<BTreeMap<_, _>>();
+
+        // Definitions can contain module paths.
+        if let Ok(modules) = serde_json::to_string(&self.rhai_config.modules) {
+            hashes.insert(
+                String::from("rhai-config:modules"),
+                cache::stable_hash(&modules),
+            );
+        }
+
+        let cached_hir = match &cache_file {
+            Some(cache_file) => self.load_def_cache(cache_file, &hashes).await,
+            None => None,
+        };
+
+        if let Some(hir) = cached_hir {
+            tracing::info!(count = defs.len(), "loaded definitions from cache");
+            self.hir = hir;
+            // The module resolver and host functions are not part of the cache.
+            self.hir.set_import_resolver(module_resolver);
+            self.hir.set_fn_name_functions(fn_name_functions);
+
+            for (url, text) in defs {
+                let document = self.parse_document(&text);
+                self.documents.insert(url, document);
+            }
+
+            self.check_operators();
+        } else {
+            self.hir.set_std_packages(std_packages);
+
+            for (url, m, _) in &metadata {
+                self.hir.add_metadata(url, m);
+            }
+
+            for (url, text) in defs {
+                self.add_document(url, &text);
+            }
+
+            self.hir.resolve_all();
+
+            if let Some(cache_file) = &cache_file {
+                self.save_def_cache(cache_file, hashes).await;
+            }
+        }
+
+        let mut script_sources = Vec::new();
+
+        for (url, text) in scripts {
+            let normalized_url = url.clone().normalize();
+            self.add_document(url, &text);
+            script_sources.extend(self.hir.source_by_url(&normalized_url));
+        }
+
+        self.hir.resolve_sources(script_sources);
+        self.load_external_modules().await;
+    }
+
+    async fn load_def_cache(
+        &self,
+        cache_file: &Path,
+        hashes: &BTreeMap<String, u64>,
+    ) -> Option<Hir> {
+        let bytes = self.env.read_file(cache_file).await.ok()?;
+
+        match serde_json::from_slice::<DefCache>(&bytes) {
+            Ok(cache) => cache.into_hir(hashes),
+            Err(error) => {
+                tracing::warn!(%error, "invalid definition cache");
+                None
+            }
+        }
+    }
+
+    async fn save_def_cache(&self, cache_file: &Path, hashes: BTreeMap<String, u64>) {
+        let cache = DefCache::new(hashes, self.hir.clone());
+
+        let bytes = match serde_json::to_vec(&cache) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                tracing::error!(%error, "failed to serialize definition cache");
+                return;
+            }
+        };
+
+        if let Err(error) = self.env.write_file(cache_file, &bytes).await {
+            tracing::error!(%error, "failed to write definition cache");
         }
-        self.hir.resolve_all();
     }
 
     pub fn add_document(&mut self, url: Url, text: &str) {
+        let mut document = self.parse_document(text);
+        let is_def = document.is_def;
+
+        // External modules stay external when they change on disk,
+        // but not once they are opened in the editor.
+        document.is_external = !self.open_documents.contains(&url)
+            && self.documents.get(&url).is_some_and(|doc| doc.is_external);
+
+        let normalized_url = url.clone().normalize();
+
+        self.hir
+            .add_source(&normalized_url, &document.parse.clone_syntax());
+        self.documents.insert(url, document);
+
+        if is_def {
+            self.check_operators();
+        }
+    }
+
+    fn parse_document(&self, text: &str) -> Document {
         let is_def = is_rhai_def(text);
 
         let parse = if is_rhai_def(text) {
@@ -266,20 +473,11 @@ impl<E: Environment> Workspace<E> {
 
         let mapper = Mapper::new_utf16(text, false);
 
-        let normalized_url = url.clone().normalize();
-
-        self.hir.add_source(&normalized_url, &parse.clone_syntax());
-        self.documents.insert(
-            url,
-            Document {
-                parse,
-                mapper,
-                is_def,
-            },
-        );
-
-        if is_def {
-            self.check_operators();
+        Document {
+            parse,
+            mapper,
+            is_def,
+            is_external: false,
         }
     }
 
@@ -320,15 +518,144 @@ impl<E: Environment> Workspace<E> {
                     self.hir.remove_source(src);
                 }
 
-                docs_to_reparse.push((uri.clone(), doc.parse.green.to_string()));
+                docs_to_reparse.push((uri.clone(), doc.parse.green.to_string(), doc.is_external));
             }
 
             doc.is_def
         });
 
-        for (uri, text) in docs_to_reparse {
-            self.add_document(uri, &text);
+        for (uri, text, is_external) in docs_to_reparse {
+            self.add_document(uri.clone(), &text);
+
+            if let Some(doc) = self.documents.get_mut(&uri) {
+                doc.is_external = is_external;
+            }
+        }
+    }
+
+    /// Load modules that are imported but not included in the workspace,
+    /// and drop the ones that are no longer imported.
+    ///
+    /// External modules are only used for analysis,
+    /// diagnostics are not published for them.
+    pub(crate) async fn load_external_modules(&mut self) {
+        self.remove_unused_external_modules();
+
+        if !self.config.modules.load_external {
+            return;
         }
+
+        // Each round loads the modules imported by
+        // the modules of the previous round.
+        for _ in 0..self.config.modules.max_external_depth {
+            let mut loaded = Vec::new();
+
+            for candidates in self.hir.missing_module_candidates() {
+                for url in candidates {
+                    if self.unavailable_modules.contains(&url) || self.documents.contains_key(&url)
+                    {
+                        continue;
+                    }
+
+                    if let Some(text) = self.read_external_module(&url).await {
+                        tracing::debug!(%url, "loaded external module");
+                        self.add_document(url.clone(), &text);
+
+                        if let Some(doc) = self.documents.get_mut(&url) {
+                            doc.is_external = true;
+                        }
+
+                        loaded.extend(self.hir.source_by_url(&url.normalize()));
+                        break;
+                    }
+
+                    self.unavailable_modules.insert(url);
+                }
+            }
+
+            if loaded.is_empty() {
+                break;
+            }
+
+            self.hir.resolve_sources(loaded);
+        }
+    }
+
+    async fn read_external_module(&self, url: &Url) -> Option<String> {
+        let path = self.env.url_to_file_path(url)?;
+        let bytes = self.env.read_file(&path).await.ok()?;
+
+        if bytes.len() > self.config.modules.max_external_size {
+            tracing::warn!(%url, size = bytes.len(), "external module is too large");
+            return None;
+        }
+
+        let text = match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(error) => {
+                tracing::warn!(%url, %error, "external module is not valid UTF-8");
+                return None;
+            }
+        };
+
+        // Definitions are only loaded from included files.
+        if is_rhai_def(&text) {
+            return None;
+        }
+
+        Some(text)
+    }
+
+    fn remove_unused_external_modules(&mut self) {
+        let mut referenced = HashSet::new();
+
+        if self.config.modules.load_external {
+            for (url, doc) in &self.documents {
+                if doc.is_external {
+                    continue;
+                }
+
+                if let Some(module) = self.document_module(url) {
+                    referenced.extend(self.hir.dependency_modules(module));
+                }
+            }
+        }
+
+        let unused = self
+            .documents
+            .iter()
+            .filter(|(url, doc)| {
+                doc.is_external
+                    && !self.open_documents.contains(*url)
+                    && self
+                        .document_module(url)
+                        .is_none_or(|module| !referenced.contains(&module))
+            })
+            .map(|(url, _)| url.clone())
+            .collect::<Vec<_>>();
+
+        for url in unused {
+            tracing::debug!(%url, "removed unused external module");
+            self.remove_document(&url);
+        }
+    }
+
+    /// The URL of the document with the given URL of a HIR source,
+    /// which is normalized unlike the URLs of the documents.
+    pub(crate) fn document_url(&self, source_url: &Url) -> Option<&Url> {
+        match self.documents.get_key_value(source_url) {
+            Some((url, _)) => Some(url),
+            None => self
+                .documents
+                .keys()
+                .find(|&url| url.clone().normalize() == *source_url),
+        }
+    }
+
+    fn document_module(&self, url: &Url) -> Option<Module> {
+        self.hir
+            .source_by_url(&url.clone().normalize())
+            .and_then(|source| self.hir.module_by_source(source))
     }
 }
 
@@ -337,4 +664,6 @@ pub struct Document {
     pub(crate) parse: Parse,
     pub(crate) mapper: Mapper,
     pub(crate) is_def: bool,
+    /// Loaded on demand for an import, but not part of the workspace.
+    pub(crate) is_external: bool,
 }
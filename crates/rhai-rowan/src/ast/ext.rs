@@ -7,7 +7,8 @@ use rowan::NodeOrToken;
 
 use super::{
     AstNode, DefOpPrecedence, Expr, Lit, LitStrTemplate, LitStrTemplateInterpolation, ObjectField,
-    Param, ParamList, Stmt, SwitchArm, SwitchArmCondition, Type, TypeObjectField, TypedParam,
+    Param, ParamList, Stmt, SwitchArm, SwitchArmCondition, Type, TypeObjectField, TypeParam,
+    TypedParam,
 };
 use super::{ExprBlock, ExprIf, T};
 use crate::syntax::{SyntaxElement, SyntaxKind, SyntaxToken};
@@ -115,6 +116,31 @@ impl super::TypedParamList {
     }
 }
 
+impl super::TypeParamList {
+    pub fn params(&self) -> impl Iterator<Item = TypeParam> {
+        self.syntax().children().filter_map(TypeParam::cast)
+    }
+}
+
+impl super::TypeFn {
+    pub fn param_types(&self) -> impl Iterator<Item = Type> {
+        self.syntax()
+            .children_with_tokens()
+            .take_while(|t| t.kind() != T!["->"])
+            .filter_map(SyntaxElement::into_node)
+            .filter_map(Type::cast)
+    }
+
+    #[must_use]
+    pub fn ret_ty(&self) -> Option<Type> {
+        self.syntax()
+            .children_with_tokens()
+            .skip_while(|t| t.kind() != T!["->"])
+            .filter_map(SyntaxElement::into_node)
+            .find_map(Type::cast)
+    }
+}
+
 impl super::Path {
     pub fn segments(&self) -> impl Iterator<Item = SyntaxToken> {
         self.syntax()
@@ -216,13 +242,10 @@ impl super::SwitchArmList {
 impl super::SwitchArm {
     #[must_use]
     pub fn pattern_expr(&self) -> Option<Expr> {
-        let fat_arrow = self.punct_arrow_fat_token();
-
-        fat_arrow
-            .and_then(|arrow| arrow.prev_sibling_or_token())
-            .and_then(SyntaxElement::into_node)
-            .and_then(Expr::cast)
-            .or_else(|| self.syntax().children().next().and_then(Expr::cast))
+        self.syntax()
+            .children_with_tokens()
+            .take_while(|c| c.kind() != T!["=>"])
+            .find_map(|c| c.into_node().and_then(Expr::cast))
     }
 
     #[must_use]
@@ -468,6 +491,11 @@ impl super::TypeObject {
 }
 
 impl super::TypeObjectField {
+    #[must_use]
+    pub fn docs_content(&self) -> String {
+        docs_to_string(self.docs())
+    }
+
     #[must_use]
     pub fn name_ident(&self) -> Option<SyntaxToken> {
         self.syntax().children_with_tokens().find_map(|t| {
@@ -478,6 +506,16 @@ impl super::TypeObjectField {
         })
     }
 
+    #[must_use]
+    pub fn name_lit_str(&self) -> Option<SyntaxToken> {
+        self.syntax().children_with_tokens().find_map(|t| {
+            if t.kind() != T!["lit_str"] {
+                return None;
+            }
+            t.into_token()
+        })
+    }
+
     #[must_use]
     pub fn name_lit(&self) -> Option<Lit> {
         self.syntax().children().find_map(Lit::cast)
@@ -247,6 +247,10 @@ pub fn parse_def_fn(ctx: &mut Context) {
         expect_token!(ctx in node, T!["ident"]);
     }
 
+    if let Some(T!["<"]) = ctx.token() {
+        parse_type_param_list(ctx);
+    }
+
     if !matches!(ctx.token(), Some(T!["("])) {
         ctx.add_error(ParseErrorKind::ExpectedToken(T!["("]));
         ctx.finish_node();
@@ -364,6 +368,45 @@ pub fn parse_def_type(ctx: &mut Context) {
     ctx.finish_node();
 }
 
+#[tracing::instrument(level = tracing::Level::TRACE, skip(ctx))]
+fn parse_type_param_list(ctx: &mut Context) {
+    ctx.start_node(TYPE_PARAM_LIST);
+    expect_token!(ctx in node, T!["<"]);
+
+    loop {
+        let token = require_token!(ctx in node);
+
+        if token == T![">"] {
+            ctx.eat();
+            break;
+        }
+
+        if token == T!["ident"] {
+            ctx.start_node(TYPE_PARAM);
+            ctx.eat();
+            ctx.finish_node();
+        } else {
+            ctx.eat_error(ParseErrorKind::ExpectedToken(T!["ident"]));
+            continue;
+        }
+
+        match require_token!(ctx in node) {
+            T![">"] => {
+                ctx.eat();
+                break;
+            }
+            T![","] => {
+                ctx.eat();
+            }
+            _ => {
+                ctx.eat_error(ParseErrorKind::ExpectedToken(T![","]));
+            }
+        }
+    }
+
+    ctx.finish_node();
+}
+
 #[tracing::instrument(level = tracing::Level::TRACE, skip(ctx))]
 fn parse_typed_param_list(ctx: &mut Context) {
     ctx.start_node(TYPED_PARAM_LIST);
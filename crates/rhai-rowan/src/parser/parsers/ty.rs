@@ -22,6 +22,7 @@ fn parse_type_bp(ctx: &mut Context, min_bp: u8) {
         T!["("] => parse_type_tuple(ctx),
         T!["ident"] => parse_type_ident(ctx),
         T!["#{"] => parse_type_object(ctx),
+        T!["|"] | T!["||"] => parse_type_fn(ctx),
         _ => {
             ctx.add_error(ParseErrorKind::UnexpectedToken);
             ctx.finish_node();
@@ -101,6 +102,55 @@ fn parse_type_array(ctx: &mut Context) {
     ctx.finish_node();
 }
 
+#[tracing::instrument(level = tracing::Level::TRACE, skip(ctx))]
+fn parse_type_fn(ctx: &mut Context) {
+    ctx.start_node(TYPE_FN);
+
+    match require_token!(ctx in node) {
+        T!["||"] => {
+            // special case
+            ctx.discard();
+            ctx.insert_token(T!["|"], "|");
+            ctx.insert_token(T!["|"], "|");
+        }
+        _ => {
+            ctx.eat();
+
+            loop {
+                let token = require_token!(ctx in node);
+                if token == T!["|"] {
+                    ctx.eat();
+                    break;
+                }
+
+                // Unions are not allowed in parameter types,
+                // the `|` would be ambiguous.
+                parse_type_bp(ctx, u8::MAX);
+
+                match require_token!(ctx in node) {
+                    T!["|"] => {
+                        ctx.eat();
+                        break;
+                    }
+                    T![","] => {
+                        ctx.eat();
+                    }
+                    _ => {
+                        ctx.eat_error(ParseErrorKind::ExpectedToken(T![","]));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(T!["->"]) = ctx.token() {
+        ctx.eat();
+        parse_type(ctx);
+    }
+
+    ctx.finish_node();
+}
+
 #[tracing::instrument(level = tracing::Level::TRACE, skip(ctx))]
 fn parse_type_unknown(ctx: &mut Context) {
     ctx.start_node(TYPE_UNKNOWN);
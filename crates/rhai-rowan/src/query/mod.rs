@@ -112,6 +112,22 @@ impl Query {
         })
     }
 
+    /// The operator token of a binary expression under the cursor.
+    #[must_use]
+    pub fn binary_op(&self) -> Option<SyntaxToken> {
+        let token = &self.before.as_ref()?.syntax;
+
+        if matches!(token.kind(), WHITESPACE | COMMENT_BLOCK | COMMENT_LINE) {
+            return None;
+        }
+
+        if token.parent()?.kind() != EXPR_BINARY {
+            return None;
+        }
+
+        Some(token.clone())
+    }
+
     #[must_use]
     pub fn binary_expr(&self) -> Option<SyntaxNode> {
         let before = match &self.before {
@@ -417,6 +417,7 @@ pub enum SyntaxKind {
     TYPE,
     TYPE_LIST,
     DEF_OP_PRECEDENCE,
+    TYPE_PARAM_LIST,
     TYPED_PARAM_LIST,
     TYPE_IDENT,
     TYPE_LIT,
@@ -424,10 +425,12 @@ pub enum SyntaxKind {
     TYPE_ARRAY,
     TYPE_TUPLE,
     TYPE_UNKNOWN,
+    TYPE_FN,
+    TYPE_UNION,
     TYPE_GENERICS,
     TYPE_OBJECT_FIELD,
     TYPED_PARAM,
-    TYPE_UNION,
+    TYPE_PARAM,
     // endregion
 
     // A marker to safely cast between u16 and syntax kinds.
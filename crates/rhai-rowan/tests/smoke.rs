@@ -1,4 +1,5 @@
 use rhai_rowan::{
+    ast::{AstNode, DefFn, Type},
     parser::{parsers::parse_expr, Operator, Parser},
     syntax::SyntaxKind::*,
 };
@@ -136,3 +137,59 @@ fn parse_ambiguous_integer_field_access() {
     parser.execute(parse_expr);
     assert!(parser.finish().errors.is_empty());
 }
+
+#[test]
+fn parse_generic_def_fn() {
+    let parse = Parser::new(
+        r#"module static;
+
+fn map<T, U>(arr: [T], f: |T| -> U) -> [U];
+fn call(f: || -> ()) -> bool;
+"#,
+    )
+    .parse_def();
+    assert!(parse.errors.is_empty(), "{:#?}", parse.errors);
+
+    let syntax = parse.into_syntax();
+    let mut fns = syntax.descendants().filter_map(DefFn::cast);
+
+    let map = fns.next().unwrap();
+    assert_eq!(map.ident_token().unwrap().text(), "map");
+    assert_eq!(
+        map.type_param_list()
+            .unwrap()
+            .params()
+            .filter_map(|p| p.ident_token())
+            .map(|t| t.text().to_string())
+            .collect::<Vec<_>>(),
+        ["T", "U"]
+    );
+
+    let Some(Type::Fn(f)) = map
+        .typed_param_list()
+        .unwrap()
+        .params()
+        .nth(1)
+        .and_then(|p| p.ty())
+    else {
+        panic!("expected a function type");
+    };
+    assert_eq!(f.param_types().count(), 1);
+    assert!(f.ret_ty().is_some());
+    assert!(matches!(map.ret_ty(), Some(Type::Array(_))));
+
+    let call = fns.next().unwrap();
+    assert!(call.type_param_list().is_none());
+
+    let Some(Type::Fn(f)) = call
+        .typed_param_list()
+        .unwrap()
+        .params()
+        .next()
+        .and_then(|p| p.ty())
+    else {
+        panic!("expected a function type");
+    };
+    assert_eq!(f.param_types().count(), 0);
+    assert!(f.ret_ty().is_some());
+}
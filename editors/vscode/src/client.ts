@@ -87,6 +87,7 @@ async function clientOpts(context: vscode.ExtensionContext): Promise<any> {
     documentSelector: [{ scheme: "file", language: "rhai" }],
     initializationOptions: {
       configuration: vscode.workspace.getConfiguration().get("rhai"),
+      cachePath: context.globalStorageUri.fsPath,
     },
     synchronize: {
       configurationSection: "rhai",
@@ -33,6 +33,13 @@ export namespace Client {
     "rhai/hirDump": {
       params: {
         workspaceUri?: string;
+        /**
+         * The output format, `text` is unstable and
+         * only meant for debugging, `json` is stable and versioned.
+         *
+         * Defaults to `text`.
+         */
+        format?: "text" | "json";
       };
       response:
         | {